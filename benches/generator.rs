@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use teapot::graph::directed_graph::DirectedGraph;
+use teapot::graph::generator::{LineageOption, RandomForestOption};
+
+fn bench_generator(c: &mut Criterion) {
+    c.bench_function("random_forest", |b| {
+        let option = RandomForestOption::new().max_depth(10).max_nodes(10_000);
+        b.iter(|| DirectedGraph::random_forest(String::from("forest"), black_box(1), &option))
+    });
+    c.bench_function("erdos_renyi", |b| {
+        b.iter(|| DirectedGraph::erdos_renyi(String::from("er"), black_box(1), 200, 0.05))
+    });
+    c.bench_function("barabasi_albert", |b| {
+        b.iter(|| DirectedGraph::barabasi_albert(String::from("ba"), black_box(1), 2000, 3))
+    });
+    c.bench_function("fuzzing_lineage", |b| {
+        let option = LineageOption::new().nodes(2000);
+        b.iter(|| DirectedGraph::fuzzing_lineage(String::from("lineage"), black_box(1), &option))
+    });
+}
+
+fn bench_algorithms(c: &mut Criterion) {
+    let dag = DirectedGraph::random_dag(String::from("dag"), 1, 500, 0.02);
+    c.bench_function("topological_sort", |b| b.iter(|| dag.topological_sort()));
+    c.bench_function("biconnected_components", |b| {
+        b.iter(|| dag.biconnected_components().len())
+    });
+    c.bench_function("louvain", |b| b.iter(|| dag.louvain().len()));
+}
+
+criterion_group!(benches, bench_generator, bench_algorithms);
+criterion_main!(benches);
@@ -9,6 +9,9 @@ pub enum GraphError<TNode: Node> {
     NodeNotExists(TNode::NodeIndex),
     IoError(String),
     ReachedRecursionLimit,
+    ParseError(String),
+    CycleDetected(TNode::NodeIndex),
+    MultipleParents(TNode::NodeIndex),
 }
 
 impl<TNode: Node> From<io::Error> for GraphError<TNode> {
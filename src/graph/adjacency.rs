@@ -0,0 +1,104 @@
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::collections::binary_heap::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use hashbrown::HashMap;
+
+/// Dense adjacency lists of DirectedGraph for algorithms working on node numbers.
+/// Nodes are numbered in `Ord` order of their indexes
+pub(crate) struct Adjacency<'a, TEdge: Edge> {
+    pub(crate) nodes: Vec<&'a <TEdge::Node as Node>::NodeIndex>,
+    pub(crate) id: HashMap<&'a <TEdge::Node as Node>::NodeIndex, usize>,
+    pub(crate) successors: Vec<Vec<(usize, &'a TEdge)>>,
+    pub(crate) predecessors: Vec<Vec<(usize, &'a TEdge)>>,
+}
+
+impl<'a, TEdge: Edge> Adjacency<'a, TEdge> {
+    /// Collects edges, and weak edges too if `weak_edges` is true
+    pub(crate) fn new(graph: &'a DirectedGraph<TEdge>, weak_edges: bool) -> Self {
+        let nodes: Vec<_> = graph.nodes().map(|v| v.index()).collect();
+        let mut id = HashMap::with_capacity(nodes.len());
+        for (i, index) in nodes.iter().enumerate() {
+            id.insert(*index, i);
+        }
+
+        let mut successors = vec![Vec::new(); nodes.len()];
+        let mut predecessors = vec![Vec::new(); nodes.len()];
+        let weak = graph.weak_edges().filter(|_| weak_edges);
+        for edge in graph.edges().chain(weak) {
+            if let (Some(&parent), Some(&child)) = (id.get(edge.parent()), id.get(edge.child())) {
+                successors[parent].push((child, edge));
+                predecessors[child].push((parent, edge));
+            }
+        }
+        for list in successors.iter_mut().chain(predecessors.iter_mut()) {
+            list.sort_by_key(|v| v.0);
+        }
+
+        Self {
+            nodes,
+            id,
+            successors,
+            predecessors,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Kahn's algorithm taking the smallest available node first.
+    /// Returns a node on a cycle as GraphError::CycleDetected if there is a cycle
+    pub(crate) fn topological_order(&self) -> Result<Vec<usize>, TEdge> {
+        let mut in_degree: Vec<usize> = self.predecessors.iter().map(|v| v.len()).collect();
+        let mut heap: BinaryHeap<Reverse<usize>> = (0..self.len())
+            .filter(|&v| in_degree[v] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(v)) = heap.pop() {
+            order.push(v);
+            for &(child, _) in self.successors[v].iter() {
+                in_degree[child] -= 1;
+                if in_degree[child] == 0 {
+                    heap.push(Reverse(child));
+                }
+            }
+        }
+        if order.len() == self.len() {
+            return Ok(order);
+        }
+
+        // Every remaining node has a remaining predecessor. Walking back them reaches a cycle
+        let mut visited = vec![false; self.len()];
+        let mut current = (0..self.len()).find(|&v| in_degree[v] > 0).unwrap_or(0);
+        while !visited[current] {
+            visited[current] = true;
+            current = match self.predecessors[current]
+                .iter()
+                .find(|(parent, _)| in_degree[*parent] > 0)
+            {
+                Some(&(parent, _)) => parent,
+                None => break,
+            };
+        }
+        Err(GraphError::CycleDetected(self.nodes[current].clone()))
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Sorts nodes topologically following edges and weak edges.
+    /// Nodes without order between them are sorted by `Ord` of their indexes.
+    /// Returns GraphError::CycleDetected if the graph is not a DAG
+    pub fn topological_sort(&self) -> Result<Vec<&<TEdge::Node as Node>::NodeIndex>, TEdge> {
+        let adjacency = Adjacency::new(self, true);
+        let order = adjacency.topological_order()?;
+        Ok(order.into_iter().map(|v| adjacency.nodes[v]).collect())
+    }
+}
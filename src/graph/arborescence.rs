@@ -0,0 +1,358 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::Add;
+
+/// Cost ordered by number of edges from the virtual root first, then by weight.
+/// The weight is `plus - minus`, kept as two sums so that unsigned weights never underflow
+#[derive(Debug, Clone, Copy)]
+struct Cost<W> {
+    virtual_edges: i64,
+    plus: W,
+    minus: W,
+}
+
+impl<W: PartialOrd + Copy + Add<Output = W>> Cost<W> {
+    fn new(virtual_edges: i64, weight: W, zero: W) -> Self {
+        Self {
+            virtual_edges,
+            plus: weight,
+            minus: zero,
+        }
+    }
+
+    /// Compares weights reversed if `maximum`
+    fn less_than(&self, other: &Self, maximum: bool) -> bool {
+        match self.virtual_edges.cmp(&other.virtual_edges) {
+            Ordering::Equal => {
+                // `self.plus - self.minus < other.plus - other.minus` without subtraction
+                let (this, that) = (self.plus + other.minus, other.plus + self.minus);
+                if maximum {
+                    that < this
+                } else {
+                    this < that
+                }
+            }
+            order => order == Ordering::Less,
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self {
+            virtual_edges: self.virtual_edges - other.virtual_edges,
+            plus: self.plus + other.minus,
+            minus: self.minus + other.plus,
+        }
+    }
+}
+
+/// Edges of contracted graph: (parent, child, cost, index of the edge in the previous level)
+type Edges<W> = Vec<(usize, usize, Cost<W>, usize)>;
+
+struct Level<W> {
+    edges: Edges<W>,
+    /// The cheapest incoming edge of each node
+    best: Vec<Option<usize>>,
+    cycle_of: Vec<Option<usize>>,
+    cycles: Vec<Vec<usize>>,
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Chooses one parent per node minimizing the total weight (Chu-Liu/Edmonds algorithm) following edges and weak edges.
+    /// * As many nodes as possible get a parent; nodes are left as roots only if it is inevitable
+    ///   (e.g. nodes without incoming edges, and one node of each cycle without incoming edges from outside)
+    /// * Returns a new graph having all nodes and selected edges (i.e. a forest where each node has at most one parent),
+    ///   and the total weight of selected edges
+    pub fn minimum_spanning_arborescence<W, F>(&self, weight: F) -> (DirectedGraph<TEdge>, W)
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        self.__spanning_arborescence(weight, false)
+    }
+
+    /// Chooses one parent per node maximizing the total weight. See DirectedGraph::minimum_spanning_arborescence()
+    pub fn maximum_spanning_arborescence<W, F>(&self, weight: F) -> (DirectedGraph<TEdge>, W)
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        self.__spanning_arborescence(weight, true)
+    }
+
+    fn __spanning_arborescence<W, F>(&self, weight: F, maximum: bool) -> (DirectedGraph<TEdge>, W)
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+
+        // Level 0: original edges and edges from virtual root (numbered n) to every node
+        let mut original: Vec<Option<&TEdge>> = Vec::new();
+        let mut edges: Edges<W> = Vec::new();
+        for v in 0..n {
+            for &(parent, edge) in adjacency.predecessors[v].iter() {
+                if parent == v {
+                    continue;
+                }
+                let cost = Cost::new(0, weight(edge), W::default());
+                edges.push((parent, v, cost, original.len()));
+                original.push(Some(edge));
+            }
+        }
+        for v in 0..n {
+            let cost = Cost::new(1, W::default(), W::default());
+            edges.push((n, v, cost, original.len()));
+            original.push(None);
+        }
+
+        // Contract cycles of the cheapest incoming edges until no cycles remain
+        let mut levels: Vec<Level<W>> = Vec::new();
+        let (mut size, mut root) = (n + 1, n);
+        let mut selected: Vec<usize> = loop {
+            let mut best: Vec<Option<usize>> = vec![None; size];
+            for (i, (_, v, cost, _)) in edges.iter().enumerate() {
+                if *v == root {
+                    continue;
+                }
+                match best[*v] {
+                    Some(j) if !cost.less_than(&edges[j].2, maximum) => (),
+                    _ => best[*v] = Some(i),
+                }
+            }
+            let parent_of = |v: usize| best[v].map_or(root, |i| edges[i].0);
+
+            let mut cycle_of: Vec<Option<usize>> = vec![None; size];
+            let mut cycles: Vec<Vec<usize>> = Vec::new();
+            let mut walked_from = vec![usize::MAX; size];
+            for start in 0..size {
+                let mut v = start;
+                while v != root && walked_from[v] == usize::MAX {
+                    walked_from[v] = start;
+                    v = parent_of(v);
+                }
+                if v != root && walked_from[v] == start {
+                    let mut cycle = Vec::new();
+                    let mut w = v;
+                    loop {
+                        cycle_of[w] = Some(cycles.len());
+                        cycle.push(w);
+                        w = parent_of(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    cycles.push(cycle);
+                }
+            }
+
+            if cycles.is_empty() {
+                break (0..size).filter_map(|v| best[v]).collect();
+            }
+
+            let mut component = vec![0; size];
+            let mut next = 0;
+            for v in 0..size {
+                if cycle_of[v].is_none() {
+                    component[v] = next;
+                    next += 1;
+                }
+            }
+            for (id, cycle) in cycles.iter().enumerate() {
+                for &v in cycle.iter() {
+                    component[v] = next + id;
+                }
+            }
+
+            let mut contracted = Vec::new();
+            for (i, &(u, v, cost, _)) in edges.iter().enumerate() {
+                if component[u] == component[v] {
+                    continue;
+                }
+                let cost = match (cycle_of[v], best[v]) {
+                    (Some(_), Some(j)) => cost.sub(&edges[j].2),
+                    _ => cost,
+                };
+                contracted.push((component[u], component[v], cost, i));
+            }
+
+            size = next + cycles.len();
+            root = component[root];
+            levels.push(Level {
+                edges: core::mem::replace(&mut edges, contracted),
+                best,
+                cycle_of,
+                cycles,
+            });
+        };
+
+        // Expand contracted cycles. Each cycle is entered by exactly one selected edge
+        while let Some(level) = levels.pop() {
+            let mut expanded: Vec<usize> = selected.iter().map(|&i| edges[i].3).collect();
+            let mut entered = vec![None; level.cycles.len()];
+            for &i in expanded.iter() {
+                let v = level.edges[i].1;
+                if let Some(id) = level.cycle_of[v] {
+                    entered[id] = Some(v);
+                }
+            }
+            for (id, cycle) in level.cycles.iter().enumerate() {
+                for &v in cycle.iter() {
+                    if Some(v) != entered[id] {
+                        expanded.extend(level.best[v]);
+                    }
+                }
+            }
+            selected = expanded;
+            edges = level.edges;
+        }
+
+        let mut selected: Vec<&TEdge> = selected
+            .into_iter()
+            .filter_map(|i| original[edges[i].3])
+            .collect();
+        selected.sort();
+
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            graph.add_node(node);
+        }
+        let mut total = W::default();
+        for edge in selected {
+            graph.add_edge(edge);
+            total = total + weight(edge);
+        }
+        (graph, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize, i64)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child, weight) in edges {
+            graph.add_edge(&TestGraphEdge::new(&parent, &child, format!("{}", weight)));
+        }
+        graph
+    }
+
+    fn weight(edge: &TestGraphEdge) -> i64 {
+        edge.label().parse().unwrap()
+    }
+
+    fn has_edge(graph: &DirectedGraph<TestGraphEdge>, parent: usize, child: usize) -> bool {
+        let arrow = DirectedEdge::new(&parent, &child);
+        graph.get_edge(&arrow).is_some() || graph.get_weak_edge(&arrow).is_some()
+    }
+
+    #[test]
+    fn test_directed_graph_spanning_arborescence_dag() {
+        /*
+           (1)     (2)
+            | \   / |
+            3  1 5  2
+            v   vv  v
+           (3)  (4) (5)
+                 |
+                 4
+                 v
+                (5)
+        */
+        let graph = build_graph(&[(1, 3, 3), (1, 4, 1), (2, 4, 5), (2, 5, 2), (4, 5, 4)]);
+
+        let (forest, total) = graph.maximum_spanning_arborescence(weight);
+        assert_eq!(total, 3 + 5 + 4);
+        assert_eq!(forest.nodes().len(), 5);
+        assert_eq!(forest.edges().len() + forest.weak_edges().len(), 3);
+        assert!(has_edge(&forest, 2, 4));
+        assert!(has_edge(&forest, 4, 5));
+
+        let (forest, total) = graph.minimum_spanning_arborescence(weight);
+        assert_eq!(total, 3 + 1 + 2);
+        assert!(has_edge(&forest, 1, 4));
+        assert!(has_edge(&forest, 2, 5));
+    }
+
+    #[test]
+    fn test_directed_graph_spanning_arborescence_cycle() {
+        /*
+           (0) -10-> (1) <-1-- (3)
+            |         ^ |       ^
+            20        1 1       1
+            |         | v       |
+            +-------> (2) ------+
+        */
+        let graph = build_graph(&[
+            (0, 1, 10),
+            (0, 2, 20),
+            (1, 2, 1),
+            (2, 1, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+        ]);
+
+        let (tree, total) = graph.minimum_spanning_arborescence(weight);
+        assert_eq!(total, 10 + 1 + 1);
+        assert!(has_edge(&tree, 0, 1));
+        assert!(has_edge(&tree, 1, 2));
+        assert!(has_edge(&tree, 2, 3));
+        #[cfg(feature = "metrics")]
+        {
+            assert_eq!(tree.parent_of(&3), Some(&2));
+            assert_eq!(tree.roots().len(), 1);
+        }
+
+        // Cycle without incoming edges from outside leaves one root
+        let graph = build_graph(&[(1, 2, 1), (2, 3, 2), (3, 1, 3)]);
+        let (tree, total) = graph.maximum_spanning_arborescence(weight);
+        assert_eq!(total, 2 + 3);
+        assert!(!has_edge(&tree, 1, 2));
+    }
+
+    #[test]
+    fn test_directed_graph_spanning_arborescence_unsigned() {
+        // Same as above with unsigned weights, which must not underflow on contracting cycles
+        let weight = |edge: &TestGraphEdge| edge.label().parse::<u64>().unwrap();
+        let graph = build_graph(&[
+            (0, 1, 10),
+            (0, 2, 20),
+            (1, 2, 1),
+            (2, 1, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+        ]);
+
+        let (tree, total) = graph.minimum_spanning_arborescence(weight);
+        assert_eq!(total, 10 + 1 + 1);
+        assert!(has_edge(&tree, 1, 2));
+
+        let (tree, total) = graph.maximum_spanning_arborescence(weight);
+        assert_eq!(total, 20 + 10 + 1);
+        assert!(has_edge(&tree, 0, 1));
+        assert!(has_edge(&tree, 0, 2));
+        assert!(has_edge(&tree, 2, 3));
+
+        let graph = build_graph(&[(1, 2, 1), (2, 3, 2), (3, 1, 3)]);
+        let (_, total) = graph.minimum_spanning_arborescence(weight);
+        assert_eq!(total, 1 + 2);
+        let (tree, total) = graph.maximum_spanning_arborescence(weight);
+        assert_eq!(total, 2 + 3);
+        assert!(!has_edge(&tree, 1, 2));
+    }
+}
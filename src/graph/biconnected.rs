@@ -0,0 +1,259 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Articulation points, bridges and biconnected components of the underlying undirected graph.
+/// See DirectedGraph::biconnected_components()
+pub struct Biconnected<'a, TEdge: Edge> {
+    articulation_points: Vec<&'a <TEdge::Node as Node>::NodeIndex>,
+    bridges: Vec<&'a TEdge>,
+    components: Vec<Vec<&'a <TEdge::Node as Node>::NodeIndex>>,
+}
+
+impl<'a, TEdge: Edge> Biconnected<'a, TEdge> {
+    /// Returns number of biconnected components
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Returns nodes whose removal increases the number of connected components, in `Ord` order
+    pub fn articulation_points(&self) -> &Vec<&'a <TEdge::Node as Node>::NodeIndex> {
+        &self.articulation_points
+    }
+
+    pub fn is_articulation_point(&self, node: &<TEdge::Node as Node>::NodeIndex) -> bool {
+        self.articulation_points.binary_search(&node).is_ok()
+    }
+
+    /// Returns edges whose removal increases the number of connected components, in `Ord` order.
+    /// Edges in both directions between the same nodes are one undirected edge, so that both are returned
+    pub fn bridges(&self) -> &Vec<&'a TEdge> {
+        &self.bridges
+    }
+
+    /// Returns nodes of each biconnected component in `Ord` order. Components are sorted too.
+    /// Articulation points belong to multiple components, and isolated nodes belong to none
+    pub fn components(&self) -> &Vec<Vec<&'a <TEdge::Node as Node>::NodeIndex>> {
+        &self.components
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Finds articulation points, bridges and biconnected components (Hopcroft-Tarjan algorithm)
+    /// treating edges and weak edges as undirected. Self loops are ignored
+    pub fn biconnected_components(&self) -> Biconnected<'_, TEdge> {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+        let mut neighbors: Vec<Vec<usize>> = (0..n)
+            .map(|v| {
+                let successors = adjacency.successors[v].iter();
+                successors
+                    .chain(adjacency.predecessors[v].iter())
+                    .map(|v| v.0)
+                    .filter(|&u| u != v)
+                    .collect()
+            })
+            .collect();
+        for list in neighbors.iter_mut() {
+            list.sort();
+            list.dedup();
+        }
+
+        const UNVISITED: usize = usize::MAX;
+        let mut discovery = vec![UNVISITED; n];
+        let mut low = vec![UNVISITED; n];
+        let mut time = 0;
+        let mut articulation = vec![false; n];
+        let mut bridges: Vec<(usize, usize)> = Vec::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut edge_stack: Vec<(usize, usize)> = Vec::new();
+        for start in 0..n {
+            if discovery[start] != UNVISITED {
+                continue;
+            }
+            discovery[start] = time;
+            low[start] = time;
+            time += 1;
+            let mut root_children = 0;
+
+            // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+            let mut stack = vec![(start, UNVISITED, 0)];
+            while let Some((v, parent, i)) = stack.last_mut() {
+                let (v, parent) = (*v, *parent);
+                if let Some(&w) = neighbors[v].get(*i) {
+                    *i += 1;
+                    if w == parent {
+                        continue;
+                    }
+                    if discovery[w] == UNVISITED {
+                        edge_stack.push((v, w));
+                        discovery[w] = time;
+                        low[w] = time;
+                        time += 1;
+                        stack.push((w, v, 0));
+                    } else if discovery[w] < discovery[v] {
+                        edge_stack.push((v, w));
+                        low[v] = low[v].min(discovery[w]);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                if parent == UNVISITED {
+                    continue;
+                }
+                low[parent] = low[parent].min(low[v]);
+                if low[v] > discovery[parent] {
+                    bridges.push((parent, v));
+                }
+                if low[v] >= discovery[parent] {
+                    if parent == start {
+                        root_children += 1;
+                    } else {
+                        articulation[parent] = true;
+                    }
+                    let mut component = Vec::new();
+                    while let Some((a, b)) = edge_stack.pop() {
+                        component.push(a);
+                        component.push(b);
+                        if (a, b) == (parent, v) {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    component.dedup();
+                    components.push(component);
+                }
+            }
+            if root_children > 1 {
+                articulation[start] = true;
+            }
+        }
+        components.sort();
+
+        let mut bridge_edges: Vec<&TEdge> = Vec::new();
+        for (a, b) in bridges {
+            for (from, to) in [(a, b), (b, a)] {
+                let edges = adjacency.successors[from].iter();
+                bridge_edges.extend(edges.filter(|v| v.0 == to).map(|v| v.1));
+            }
+        }
+        bridge_edges.sort();
+
+        Biconnected {
+            articulation_points: (0..n)
+                .filter(|&v| articulation[v])
+                .map(|v| adjacency.nodes[v])
+                .collect(),
+            bridges: bridge_edges,
+            components: components
+                .into_iter()
+                .map(|v| v.into_iter().map(|v| adjacency.nodes[v]).collect())
+                .collect(),
+        }
+    }
+
+    /// Returns nodes whose removal disconnects the underlying undirected graph.
+    /// See DirectedGraph::biconnected_components()
+    pub fn articulation_points(&self) -> Vec<&<TEdge::Node as Node>::NodeIndex> {
+        self.biconnected_components().articulation_points
+    }
+
+    /// Returns edges whose removal disconnects the underlying undirected graph.
+    /// See DirectedGraph::biconnected_components()
+    pub fn bridges(&self) -> Vec<&TEdge> {
+        self.biconnected_components().bridges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child) in edges {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_biconnected_components() {
+        /*
+           (1) --> (2)          (7) <-> (8)
+            ^     /
+            |    v
+           (3) <+ --> (4) --> (5) --> (6) ↺
+        */
+        let mut graph = build_graph(&[
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (6, 6),
+            (7, 8),
+            (8, 7),
+        ]);
+        graph.add_node(&BasicNode::new(&9));
+        let biconnected = graph.biconnected_components();
+
+        assert_eq!(biconnected.articulation_points(), &vec![&3, &4, &5]);
+        assert!(biconnected.is_articulation_point(&4));
+        assert!(!biconnected.is_articulation_point(&1));
+
+        let bridges: Vec<(usize, usize)> = biconnected
+            .bridges()
+            .iter()
+            .map(|v| (*v.parent(), *v.child()))
+            .collect();
+        assert_eq!(bridges, [(3, 4), (4, 5), (5, 6), (7, 8), (8, 7)]);
+        assert_eq!(graph.bridges().len(), 5);
+
+        assert_eq!(biconnected.len(), 5);
+        assert_eq!(
+            biconnected.components(),
+            &vec![
+                vec![&1, &2, &3],
+                vec![&3, &4],
+                vec![&4, &5],
+                vec![&5, &6],
+                vec![&7, &8],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directed_graph_articulation_points_deep() {
+        // Deep enough to overflow the stack if DFS recursed.
+        // NOTE: Edges are added from the bottom so that DirectedGraph::root_of() stays shallow with `metrics`
+        let edges: Vec<(usize, usize)> = (0..100_000).rev().map(|v| (v, v + 1)).collect();
+        let graph = build_graph(&edges);
+        assert_eq!(graph.articulation_points().len(), 99_999);
+        assert_eq!(graph.bridges().len(), 100_000);
+    }
+}
@@ -0,0 +1,401 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Options for iterative centrality measures (DirectedGraph::pagerank() and DirectedGraph::hits())
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentralityOption {
+    /// (PageRank only) Probability to follow an edge instead of jumping to a random node
+    pub damping: f64,
+    pub max_iterations: usize,
+    /// Stops iteration when the sum of absolute changes of scores is less than `number of nodes * tolerance`
+    pub tolerance: f64,
+}
+
+impl Default for CentralityOption {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            max_iterations: 100,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+impl CentralityOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+/// Scores of iterative centrality measure. Scores sum up to 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scores<'a, TIndex> {
+    pub scores: BTreeMap<&'a TIndex, f64>,
+    pub iterations: usize,
+    /// False if iteration stopped by `max_iterations`
+    pub converged: bool,
+}
+
+/// Hub and authority scores of DirectedGraph::hits(). Each of them sums up to 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitsScores<'a, TIndex> {
+    pub hubs: BTreeMap<&'a TIndex, f64>,
+    pub authorities: BTreeMap<&'a TIndex, f64>,
+    pub iterations: usize,
+    /// False if iteration stopped by `max_iterations`
+    pub converged: bool,
+}
+
+/// NOTE: f64::abs() is not available in no_std
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| if x > y { x - y } else { y - x })
+        .sum()
+}
+
+fn normalize(scores: &mut [f64], by: f64) {
+    if by > 0.0 {
+        for score in scores.iter_mut() {
+            *score /= by;
+        }
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// PageRank following edges and weak edges. Dangling nodes jump to random nodes
+    pub fn pagerank(
+        &self,
+        option: &CentralityOption,
+    ) -> Scores<'_, <TEdge::Node as Node>::NodeIndex> {
+        self.personalized_pagerank(option, |_| 1.0)
+    }
+
+    /// PageRank jumping to nodes with probability proportional to `personalization`.
+    /// Falls back to uniform distribution if weights of all nodes are zero
+    pub fn personalized_pagerank<F>(
+        &self,
+        option: &CentralityOption,
+        personalization: F,
+    ) -> Scores<'_, <TEdge::Node as Node>::NodeIndex>
+    where
+        F: Fn(&<TEdge::Node as Node>::NodeIndex) -> f64,
+    {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+        if n == 0 {
+            return Scores {
+                scores: BTreeMap::new(),
+                iterations: 0,
+                converged: true,
+            };
+        }
+
+        let mut jump: Vec<f64> = adjacency
+            .nodes
+            .iter()
+            .map(|v| {
+                let weight = personalization(v);
+                if weight > 0.0 {
+                    weight
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let sum: f64 = jump.iter().sum();
+        if sum > 0.0 {
+            normalize(&mut jump, sum);
+        } else {
+            jump = vec![1.0 / n as f64; n];
+        }
+
+        let mut scores = jump.clone();
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < option.max_iterations {
+            iterations += 1;
+            let dangling: f64 = (0..n)
+                .filter(|&v| adjacency.successors[v].len() == 0)
+                .map(|v| scores[v])
+                .sum();
+            let mut next: Vec<f64> = jump
+                .iter()
+                .map(|p| (1.0 - option.damping) * p + option.damping * dangling * p)
+                .collect();
+            for v in 0..n {
+                let degree = adjacency.successors[v].len();
+                for &(child, _) in adjacency.successors[v].iter() {
+                    next[child] += option.damping * scores[v] / degree as f64;
+                }
+            }
+            let change = distance(&scores, &next);
+            scores = next;
+            if change < n as f64 * option.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        Scores {
+            scores: adjacency.nodes.into_iter().zip(scores).collect(),
+            iterations,
+            converged,
+        }
+    }
+
+    /// Hub and authority scores (Kleinberg's HITS) following edges and weak edges
+    pub fn hits(
+        &self,
+        option: &CentralityOption,
+    ) -> HitsScores<'_, <TEdge::Node as Node>::NodeIndex> {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+
+        let mut hubs = vec![1.0; n];
+        let mut authorities = vec![0.0; n];
+        let mut iterations = 0;
+        let mut converged = n == 0;
+        while !converged && iterations < option.max_iterations {
+            iterations += 1;
+            authorities = (0..n)
+                .map(|v| adjacency.predecessors[v].iter().map(|p| hubs[p.0]).sum())
+                .collect();
+            let mut next: Vec<f64> = (0..n)
+                .map(|v| {
+                    adjacency.successors[v]
+                        .iter()
+                        .map(|c| authorities[c.0])
+                        .sum()
+                })
+                .collect();
+            let max = next.iter().cloned().fold(0.0, f64::max);
+            normalize(&mut next, max);
+            let max = authorities.iter().cloned().fold(0.0, f64::max);
+            normalize(&mut authorities, max);
+
+            converged = distance(&hubs, &next) < n as f64 * option.tolerance;
+            hubs = next;
+        }
+
+        let sum = hubs.iter().sum();
+        normalize(&mut hubs, sum);
+        let sum = authorities.iter().sum();
+        normalize(&mut authorities, sum);
+        HitsScores {
+            hubs: adjacency.nodes.iter().cloned().zip(hubs).collect(),
+            authorities: adjacency.nodes.into_iter().zip(authorities).collect(),
+            iterations,
+            converged,
+        }
+    }
+
+    /// Betweenness centrality (Brandes' algorithm) following edges and weak edges without normalization.
+    /// i.e. sum of fractions of shortest paths between other pairs passing through each node
+    pub fn betweenness_centrality(&self) -> BTreeMap<&<TEdge::Node as Node>::NodeIndex, f64> {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+
+        let mut betweenness = vec![0.0; n];
+        for source in 0..n {
+            let mut order = Vec::with_capacity(n);
+            let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut paths = vec![0.0; n];
+            let mut distance: Vec<Option<usize>> = vec![None; n];
+            paths[source] = 1.0;
+            distance[source] = Some(0);
+
+            let mut queue = VecDeque::from([source]);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let next = distance[v].map(|d| d + 1);
+                for &(w, _) in adjacency.successors[v].iter() {
+                    if distance[w].is_none() {
+                        distance[w] = next;
+                        queue.push_back(w);
+                    }
+                    if distance[w] == next {
+                        paths[w] += paths[v];
+                        predecessors[w].push(v);
+                    }
+                }
+            }
+
+            let mut dependency = vec![0.0; n];
+            for &w in order.iter().rev() {
+                for &v in predecessors[w].iter() {
+                    dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+                }
+                if w != source {
+                    betweenness[w] += dependency[w];
+                }
+            }
+        }
+
+        adjacency.nodes.into_iter().zip(betweenness).collect()
+    }
+
+    /// Closeness centrality by distances to nodes reachable from each node following edges and weak edges.
+    /// Scaled by the fraction of reachable nodes (Wasserman and Faust) so that nodes reaching only a few nodes are not overrated.
+    /// Nodes reaching no nodes have zero
+    pub fn closeness_centrality(&self) -> BTreeMap<&<TEdge::Node as Node>::NodeIndex, f64> {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+
+        let mut closeness = vec![0.0; n];
+        for source in 0..n {
+            let mut distance: Vec<Option<usize>> = vec![None; n];
+            distance[source] = Some(0);
+            let (mut reachable, mut total) = (0usize, 0usize);
+
+            let mut queue = VecDeque::from([source]);
+            while let Some(v) = queue.pop_front() {
+                let d = distance[v].unwrap_or(0);
+                for &(w, _) in adjacency.successors[v].iter() {
+                    if distance[w].is_none() {
+                        distance[w] = Some(d + 1);
+                        reachable += 1;
+                        total += d + 1;
+                        queue.push_back(w);
+                    }
+                }
+            }
+            if total > 0 && n > 1 {
+                closeness[source] =
+                    (reachable as f64 / (n - 1) as f64) * (reachable as f64 / total as f64);
+            }
+        }
+
+        adjacency.nodes.into_iter().zip(closeness).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::graph::centrality::CentralityOption;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::collections::BTreeMap;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child) in edges {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    fn assert_scores(actual: &BTreeMap<&usize, f64>, expected: &[(usize, f64)]) {
+        let actual: Vec<(usize, f64)> = actual.iter().map(|(k, v)| (**k, *v)).collect();
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for ((k, v), (l, w)) in actual.iter().zip(expected.iter()) {
+            assert_eq!(k, l);
+            assert!(v - w < 1e-4 && w - v < 1e-4, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn test_directed_graph_pagerank() {
+        let graph = build_graph(&[(1, 2), (2, 3), (3, 1)]);
+        let pagerank = graph.pagerank(&CentralityOption::new());
+        assert!(pagerank.converged);
+        assert_scores(
+            &pagerank.scores,
+            &[(1, 1.0 / 3.0), (2, 1.0 / 3.0), (3, 1.0 / 3.0)],
+        );
+
+        // Node 3 is dangling
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 3)]);
+        let pagerank = graph.pagerank(&CentralityOption::new().tolerance(1e-10));
+        assert!(pagerank.converged);
+        assert_scores(&pagerank.scores, &[(1, 0.1976), (2, 0.2816), (3, 0.5208)]);
+
+        let pagerank =
+            graph.personalized_pagerank(
+                &CentralityOption::new(),
+                |v| {
+                    if *v == 1 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                },
+            );
+        assert!(pagerank.scores[&1] > pagerank.scores[&2]);
+
+        let pagerank = graph.pagerank(&CentralityOption::new().max_iterations(1));
+        assert_eq!(pagerank.iterations, 1);
+        assert!(!pagerank.converged);
+    }
+
+    #[test]
+    fn test_directed_graph_hits() {
+        let graph = build_graph(&[(1, 3), (2, 3), (2, 4)]);
+        let hits = graph.hits(&CentralityOption::new());
+        assert!(hits.converged);
+        assert!(hits.hubs[&2] > hits.hubs[&1]);
+        assert!(hits.authorities[&3] > hits.authorities[&4]);
+        assert_eq!(hits.authorities[&1], 0.0);
+        assert_eq!(hits.hubs[&3], 0.0);
+        let sum: f64 = hits.hubs.values().sum();
+        assert!(sum - 1.0 < 1e-9 && 1.0 - sum < 1e-9);
+    }
+
+    #[test]
+    fn test_directed_graph_betweenness_and_closeness_centrality() {
+        /*
+           (1)
+           / \
+         (2) (3)
+           \ /
+           (4) --> (5)
+        */
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5)]);
+        assert_scores(
+            &graph.betweenness_centrality(),
+            &[(1, 0.0), (2, 1.0), (3, 1.0), (4, 3.0), (5, 0.0)],
+        );
+        assert_scores(
+            &graph.closeness_centrality(),
+            &[
+                (1, 1.0 * 4.0 / 7.0),
+                (2, 0.5 * 2.0 / 3.0),
+                (3, 0.5 * 2.0 / 3.0),
+                (4, 0.25),
+                (5, 0.0),
+            ],
+        );
+    }
+}
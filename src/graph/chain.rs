@@ -0,0 +1,572 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::node_index::NodeIndex;
+use crate::node::Node;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use hashbrown::{HashMap, HashSet};
+
+/// Index of DirectedGraph::compress_chains() result.
+/// A chain is identified with its first node
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ChainIndex<TIndex: NodeIndex> {
+    Node(TIndex),
+    Chain(TIndex),
+}
+
+impl<TIndex: NodeIndex> NodeIndex for ChainIndex<TIndex> {}
+
+impl<TIndex: NodeIndex> Default for ChainIndex<TIndex> {
+    fn default() -> Self {
+        Self::Node(TIndex::default())
+    }
+}
+
+impl<TIndex: NodeIndex> ChainIndex<TIndex> {
+    /// Returns the original node or the first node of the chain
+    pub fn inner(&self) -> &TIndex {
+        match self {
+            Self::Node(index) => index,
+            Self::Chain(index) => index,
+        }
+    }
+}
+
+impl<TIndex: NodeIndex> fmt::Display for ChainIndex<TIndex> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(index) => write!(f, "{}", index),
+            Self::Chain(index) => write!(f, "chain:{}", index),
+        }
+    }
+}
+
+/// Original node, or summary of a chain holding its endpoints and the number of nodes
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default, Hash)]
+pub struct ChainNode<TNode: Node> {
+    first: TNode,
+    last: TNode,
+    count: usize,
+    index: ChainIndex<TNode::NodeIndex>,
+}
+
+impl<TNode: Node> Node for ChainNode<TNode> {
+    type NodeIndex = ChainIndex<TNode::NodeIndex>;
+
+    fn implicit_new(index: &Self::NodeIndex) -> Self {
+        let node = TNode::implicit_new(index.inner());
+        Self {
+            first: node.clone(),
+            last: node,
+            count: 1,
+            index: index.clone(),
+        }
+    }
+
+    fn index(&self) -> &Self::NodeIndex {
+        &self.index
+    }
+}
+
+impl<TNode: Node> ChainNode<TNode> {
+    pub fn node(node: &TNode) -> Self {
+        Self {
+            first: node.clone(),
+            last: node.clone(),
+            count: 1,
+            index: ChainIndex::Node(node.index().clone()),
+        }
+    }
+
+    pub fn chain(first: &TNode, last: &TNode, count: usize) -> Self {
+        Self {
+            first: first.clone(),
+            last: last.clone(),
+            count,
+            index: ChainIndex::Chain(first.index().clone()),
+        }
+    }
+
+    pub fn is_chain(&self) -> bool {
+        match self.index {
+            ChainIndex::Node(_) => false,
+            ChainIndex::Chain(_) => true,
+        }
+    }
+
+    /// Returns the original node or the first node of the chain
+    pub fn first(&self) -> &TNode {
+        &self.first
+    }
+
+    /// Returns the original node or the last node of the chain
+    pub fn last(&self) -> &TNode {
+        &self.last
+    }
+
+    /// Returns number of original nodes represented by this node
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<TNode: Node> fmt::Display for ChainNode<TNode> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_chain() {
+            write!(f, "{} .. {} ({} nodes)", self.first, self.last, self.count)
+        } else {
+            write!(f, "{}", self.first)
+        }
+    }
+}
+
+/// Mapping from summary nodes to the original nodes and edges inside chains. See DirectedGraph::compress_chains()
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChainMap<TEdge: Edge> {
+    chains: BTreeMap<<TEdge::Node as Node>::NodeIndex, Vec<<TEdge::Node as Node>::NodeIndex>>,
+    nodes: Vec<TEdge::Node>,
+    edges: Vec<TEdge>,
+    weak_edges: Vec<TEdge>,
+}
+
+impl<TEdge: Edge> ChainMap<TEdge> {
+    fn new() -> Self {
+        Self {
+            chains: BTreeMap::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            weak_edges: Vec::new(),
+        }
+    }
+
+    /// Returns number of chains
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+
+    /// Returns all nodes of the chain starting with `first` in order
+    pub fn get(
+        &self,
+        first: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Option<&Vec<<TEdge::Node as Node>::NodeIndex>> {
+        self.chains.get(first)
+    }
+
+    /// Iterates over first nodes and all nodes of chains
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            &<TEdge::Node as Node>::NodeIndex,
+            &Vec<<TEdge::Node as Node>::NodeIndex>,
+        ),
+    > {
+        self.chains.iter()
+    }
+
+    /// Returns original nodes in chains
+    pub fn nodes(&self) -> &Vec<TEdge::Node> {
+        &self.nodes
+    }
+
+    /// Returns edges inside chains
+    pub fn edges(&self) -> &Vec<TEdge> {
+        &self.edges
+    }
+
+    /// Returns weak edges inside chains
+    pub fn weak_edges(&self) -> &Vec<TEdge> {
+        &self.weak_edges
+    }
+}
+
+pub type ChainEdge<TNode> = BasicEdge<ChainNode<TNode>>;
+
+/// Converts errors of compressed graph into the original ones
+pub(crate) fn from_chain_error<TNode: Node>(
+    error: GraphError<ChainNode<TNode>>,
+) -> GraphError<TNode> {
+    match error {
+        GraphError::NodeNotExists(index) => GraphError::NodeNotExists(index.inner().clone()),
+        GraphError::IoError(why) => GraphError::IoError(why),
+        GraphError::ReachedRecursionLimit => GraphError::ReachedRecursionLimit,
+        GraphError::ParseError(why) => GraphError::ParseError(why),
+        GraphError::CycleDetected(index) => GraphError::CycleDetected(index.inner().clone()),
+        GraphError::MultipleParents(index) => GraphError::MultipleParents(index.inner().clone()),
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Collapses maximal chains of nodes having exactly one incoming and one outgoing edge into summary nodes
+    /// if the chain has more than `min_length` nodes.
+    /// * Degrees count weak edges too, so nodes touched by weak edges (e.g. self loops) break chains
+    /// * Edges into and out of a chain keep their labels and kinds (i.e. edge or weak edge). Edges inside chains are moved to ChainMap
+    /// * Returns the compressed graph and ChainMap to restore the original graph (See DirectedGraph::expand_chains())
+    pub fn compress_chains(
+        &self,
+        min_length: usize,
+    ) -> (DirectedGraph<ChainEdge<TEdge::Node>>, ChainMap<TEdge>) {
+        let mut in_edge: HashMap<&<TEdge::Node as Node>::NodeIndex, (usize, &TEdge)> =
+            HashMap::with_capacity(self.nodes().len());
+        let mut out_edge: HashMap<&<TEdge::Node as Node>::NodeIndex, (usize, &TEdge)> =
+            HashMap::with_capacity(self.nodes().len());
+        for edge in self.edges().chain(self.weak_edges()) {
+            in_edge.entry(edge.child()).or_insert((0, edge)).0 += 1;
+            out_edge.entry(edge.parent()).or_insert((0, edge)).0 += 1;
+        }
+        let is_interior = |index: &<TEdge::Node as Node>::NodeIndex| {
+            in_edge.get(index).map_or(false, |v| v.0 == 1)
+                && out_edge.get(index).map_or(false, |v| v.0 == 1)
+        };
+
+        // NOTE: Cycles consisting of interior nodes only have no start, and they are kept as they are
+        let mut chains: ChainMap<TEdge> = ChainMap::new();
+        let mut chain_of = HashMap::with_capacity(8);
+        for node in self.nodes() {
+            let start = node.index();
+            if !is_interior(start) || is_interior(in_edge[start].1.parent()) {
+                continue;
+            }
+            let mut chain = Vec::new();
+            let mut visited = HashSet::with_capacity(8);
+            let mut current = start;
+            while is_interior(current) && visited.insert(current) {
+                chain.push(current.clone());
+                current = out_edge[current].1.child();
+            }
+            if chain.len() > min_length {
+                for index in chain.iter() {
+                    chain_of.insert(index.clone(), start.clone());
+                }
+                chains.chains.insert(start.clone(), chain);
+            }
+        }
+
+        let map_index = |index: &<TEdge::Node as Node>::NodeIndex| match chain_of.get(index) {
+            Some(first) => ChainIndex::Chain(first.clone()),
+            None => ChainIndex::Node(index.clone()),
+        };
+        let node_of = |index: &<TEdge::Node as Node>::NodeIndex| match self.get_node(index) {
+            Some(node) => node.clone(),
+            None => TEdge::Node::implicit_new(index),
+        };
+
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            if !chain_of.contains_key(node.index()) {
+                graph.add_node(&ChainNode::node(node));
+            }
+        }
+        for chain in chains.chains.values() {
+            chains.nodes.extend(chain.iter().map(&node_of));
+            graph.add_node(&ChainNode::chain(
+                &node_of(&chain[0]),
+                &node_of(&chain[chain.len() - 1]),
+                chain.len(),
+            ));
+        }
+        // NOTE: Edges keep their kinds; DirectedGraph::add_edge() would turn some edges weak depending on the order.
+        // DirectedGraph::parent_of() follows the edge added last, so that such edges are added last
+        #[cfg(feature = "metrics")]
+        let is_parent = |edge: &TEdge| self.parent_of(edge.child()) == Some(edge.parent());
+        #[cfg(not(feature = "metrics"))]
+        let is_parent = |_: &TEdge| false;
+        let mut edges: Vec<(bool, bool, &TEdge)> = self
+            .edges()
+            .map(|v| (false, is_parent(v), v))
+            .chain(self.weak_edges().map(|v| (true, false, v)))
+            .collect();
+        edges.sort();
+        for (weak, _, edge) in edges {
+            let parent = map_index(edge.parent());
+            let child = map_index(edge.child());
+            if parent == child && chain_of.contains_key(edge.parent()) {
+                // Edge inside chain
+                if weak {
+                    chains.weak_edges.push(edge.clone());
+                } else {
+                    chains.edges.push(edge.clone());
+                }
+                continue;
+            }
+            let edge = ChainEdge::new(&parent, &child, edge.label().clone());
+            if weak {
+                graph.add_weak_edge(&edge);
+            } else {
+                graph.add_strong_edge(&edge);
+            }
+        }
+
+        chains.edges.sort();
+        chains.weak_edges.sort();
+        (graph, chains)
+    }
+}
+
+impl<TNode: Node> DirectedGraph<ChainEdge<TNode>> {
+    /// Restores the graph given to DirectedGraph::compress_chains() from its result.
+    /// Edges and weak edges are restored as they were, and so is DirectedGraph::parent_of() with `metrics`
+    pub fn expand_chains(
+        &self,
+        chains: &ChainMap<BasicEdge<TNode>>,
+    ) -> DirectedGraph<BasicEdge<TNode>> {
+        let map_parent = |index: &ChainIndex<TNode::NodeIndex>| match index {
+            ChainIndex::Node(index) => index.clone(),
+            ChainIndex::Chain(first) => match chains.get(first).and_then(|v| v.last()) {
+                Some(last) => last.clone(),
+                None => first.clone(),
+            },
+        };
+
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            if !node.is_chain() {
+                graph.add_node(node.first());
+            }
+        }
+        for node in chains.nodes() {
+            graph.add_node(node);
+        }
+
+        // NOTE: Keep kinds of edges and DirectedGraph::parent_of() as DirectedGraph::compress_chains() does
+        #[cfg(feature = "metrics")]
+        let is_parent =
+            |edge: &ChainEdge<TNode>| self.parent_of(edge.child()) == Some(edge.parent());
+        #[cfg(not(feature = "metrics"))]
+        let is_parent = |_: &ChainEdge<TNode>| false;
+        let mut edges: Vec<(bool, BasicEdge<TNode>)> = self
+            .edges()
+            .map(|v| {
+                let edge = BasicEdge::new(
+                    &map_parent(v.parent()),
+                    v.child().inner(),
+                    v.label().clone(),
+                );
+                (is_parent(v), edge)
+            })
+            // Each node inside chains has only one incoming edge
+            .chain(chains.edges().iter().map(|v| (true, v.clone())))
+            .collect();
+        edges.sort();
+        for (_, edge) in edges.iter() {
+            graph.add_strong_edge(edge);
+        }
+        let weak_edges = self.weak_edges().map(|v| {
+            BasicEdge::new(
+                &map_parent(v.parent()),
+                v.child().inner(),
+                v.label().clone(),
+            )
+        });
+        for edge in weak_edges.chain(chains.weak_edges().iter().cloned()) {
+            graph.add_weak_edge(&edge);
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::graph::chain::ChainIndex;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::writer_option::WriterOption;
+    use crate::node::basic_node::BasicNode;
+    use crate::node::Node;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+              (1)
+              / \
+            (2) (6)
+             |
+            (3)
+             |
+            (4)
+             |
+            (5)
+            / \
+          (7) (8)
+        */
+        for (parent, child) in [(1, 2), (2, 3), (3, 4), (4, 5), (1, 6), (5, 7), (5, 8)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_compress_chains() {
+        let graph = build_graph();
+
+        let (compressed, chains) = graph.compress_chains(1);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains.get(&2), Some(&vec![2, 3, 4]));
+        assert_eq!(compressed.nodes().len(), 6);
+
+        let summary = compressed.get_node(&ChainIndex::Chain(2)).unwrap();
+        assert_eq!(summary.count(), 3);
+        assert_eq!(summary.last().index(), &4);
+        assert_eq!(format!("{}", summary), "2 .. 4 (3 nodes)");
+
+        let label_of = |parent: ChainIndex<usize>, child: ChainIndex<usize>| {
+            compressed
+                .get_edge(&DirectedEdge::new(&parent, &child))
+                .map(|v| v.label().clone())
+        };
+        assert_eq!(
+            label_of(ChainIndex::Node(1), ChainIndex::Chain(2)),
+            Some(String::from("1->2"))
+        );
+        assert_eq!(
+            label_of(ChainIndex::Chain(2), ChainIndex::Node(5)),
+            Some(String::from("4->5"))
+        );
+
+        // Leaf (6) is not a part of chains
+        assert!(compressed.get_node(&ChainIndex::Node(6)).is_some());
+
+        // Too short chains are kept as they are
+        let (compressed, chains) = graph.compress_chains(3);
+        assert_eq!(chains.len(), 0);
+        assert_eq!(compressed.nodes().len(), graph.nodes().len());
+    }
+
+    #[test]
+    fn test_directed_graph_expand_chains() {
+        /*
+              (1) <~~~~~~~~~~~+
+              / \             :
+            (2) (6)           :
+             |                :
+            (3)               :
+             :                :
+            (4)      (10)     :
+             |                :
+            (5)               :
+            / \               :
+          (7) (8)             :
+           :                  :
+           +~~~~~~~~~~~~~~~~~~+
+        */
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (parent, child) in [(1, 2), (2, 3), (4, 5), (1, 6), (5, 7), (5, 8)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        for (parent, child) in [(3, 4), (7, 1)] {
+            graph.add_weak_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph.add_node(&BasicNode::new(&10));
+
+        let (compressed, chains) = graph.compress_chains(1);
+        assert_eq!(chains.get(&2), Some(&vec![2, 3, 4]));
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains.nodes().len(), 3);
+        assert_eq!(chains.edges().len(), 1);
+        assert_eq!(chains.weak_edges()[0].label(), "3->4");
+
+        let expanded = compressed.expand_chains(&chains);
+        let sorted = |graph: &DirectedGraph<TestGraphEdge>, weak: bool| {
+            let mut edges: Vec<TestGraphEdge> = match weak {
+                false => graph.edges().cloned().collect(),
+                true => graph.weak_edges().cloned().collect(),
+            };
+            edges.sort();
+            edges
+        };
+        assert_eq!(sorted(&expanded, false), sorted(&graph, false));
+        assert_eq!(sorted(&expanded, true), sorted(&graph, true));
+        assert_eq!(expanded.nodes().len(), graph.nodes().len());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_directed_graph_expand_chains_keeps_kinds() {
+        /*
+              (1)
+             / | \
+           (2)<-(3) (4)
+                     |
+                    (5)
+                     |
+                    (6)
+                     |
+                    (7)
+        */
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (parent, child) in [(1, 2), (3, 2), (1, 4), (1, 3), (4, 5), (5, 6), (6, 7)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        assert!(graph.get_edge(&DirectedEdge::new(&3, &2)).is_some());
+        assert_eq!(graph.parent_of(&2), Some(&3));
+
+        let (compressed, chains) = graph.compress_chains(2);
+        assert_eq!(chains.get(&4), Some(&vec![4, 5, 6]));
+        assert_eq!(compressed.edges().len(), graph.edges().len() - 2);
+        assert_eq!(compressed.weak_edges().len(), 0);
+
+        let expanded = compressed.expand_chains(&chains);
+        let mut edges: Vec<&TestGraphEdge> = expanded.edges().collect();
+        edges.sort();
+        let mut expected: Vec<&TestGraphEdge> = graph.edges().collect();
+        expected.sort();
+        assert_eq!(edges, expected);
+        assert_eq!(expanded.weak_edges().len(), 0);
+        for node in graph.nodes() {
+            assert_eq!(
+                expanded.parent_of(node.index()),
+                graph.parent_of(node.index())
+            );
+        }
+    }
+
+    #[test]
+    fn test_directed_graph_dot_write_compress_chains() {
+        let graph = build_graph();
+
+        let mut out = Vec::new();
+        let option = WriterOption::new().compress_chains(1);
+        assert!(graph.dot_write_with_option(&mut out, &option).is_ok());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\"2 .. 4 (3 nodes)\""), "{}", out);
+        assert!(!out.contains("\"3\""), "{}", out);
+
+        let mut out = Vec::new();
+        assert!(graph.gml_write_with_option(&mut out, &option).is_ok());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("label \"2 .. 4 (3 nodes)\""), "{}", out);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Gains smaller than this are treated as no gain to avoid moving nodes back and forth by rounding errors
+const EPSILON: f64 = 1e-10;
+
+/// Rounds of DirectedGraph::label_propagation() before giving up convergence
+const LABEL_PROPAGATION_MAX_ROUNDS: usize = 100;
+
+/// Communities of nodes. See DirectedGraph::louvain() and DirectedGraph::label_propagation()
+pub struct Communities<'a, TEdge: Edge> {
+    /// Node --> Community id. Ids are numbered from 0 in `Ord` order of the smallest members
+    community: BTreeMap<&'a <TEdge::Node as Node>::NodeIndex, usize>,
+    len: usize,
+    modularity: f64,
+}
+
+impl<'a, TEdge: Edge> Communities<'a, TEdge> {
+    /// Returns number of communities
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn community_of(&self, node: &<TEdge::Node as Node>::NodeIndex) -> Option<usize> {
+        self.community.get(node).copied()
+    }
+
+    /// Returns nodes and their community ids in `Ord` order of nodes
+    pub fn iter(&self) -> impl Iterator<Item = (&&'a <TEdge::Node as Node>::NodeIndex, &usize)> {
+        self.community.iter()
+    }
+
+    /// Returns members of each community in `Ord` order, indexed by community ids
+    pub fn members(&self) -> Vec<Vec<&'a <TEdge::Node as Node>::NodeIndex>> {
+        let mut members = vec![Vec::new(); self.len];
+        for (node, &id) in self.community.iter() {
+            members[id].push(*node);
+        }
+        members
+    }
+
+    /// Returns modularity of the communities on the weighted undirected view of the graph
+    pub fn modularity(&self) -> f64 {
+        self.modularity
+    }
+}
+
+/// Symmetric weighted adjacency. `neighbors[v]` has `(v, weight)` for (aggregated) self loops
+struct Undirected {
+    neighbors: Vec<Vec<(usize, f64)>>,
+}
+
+impl Undirected {
+    /// Sums weights of edges in both directions between the same nodes. Self loops are ignored
+    fn new<TEdge: Edge, F: Fn(&TEdge) -> f64>(adjacency: &Adjacency<'_, TEdge>, weight: F) -> Self {
+        let mut maps: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); adjacency.len()];
+        for (v, successors) in adjacency.successors.iter().enumerate() {
+            for &(child, edge) in successors.iter().filter(|(child, _)| *child != v) {
+                let w = weight(edge);
+                *maps[v].entry(child).or_insert(0.0) += w;
+                *maps[child].entry(v).or_insert(0.0) += w;
+            }
+        }
+        Self {
+            neighbors: maps.into_iter().map(|v| v.into_iter().collect()).collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    fn degrees(&self) -> Vec<f64> {
+        self.neighbors
+            .iter()
+            .map(|v| v.iter().map(|v| v.1).sum())
+            .collect()
+    }
+
+    fn modularity(&self, community: &[usize], len: usize) -> f64 {
+        let degree = self.degrees();
+        let total: f64 = degree.iter().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let mut inside = vec![0.0; len];
+        let mut sum = vec![0.0; len];
+        for (v, neighbors) in self.neighbors.iter().enumerate() {
+            sum[community[v]] += degree[v];
+            for &(u, w) in neighbors.iter() {
+                if community[u] == community[v] {
+                    inside[community[v]] += w;
+                }
+            }
+        }
+        (0..len)
+            .map(|c| inside[c] / total - (sum[c] / total) * (sum[c] / total))
+            .sum()
+    }
+
+    /// Merges nodes of the same community into a node
+    fn aggregate(&self, community: &[usize], len: usize) -> Self {
+        let mut maps: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); len];
+        for (v, neighbors) in self.neighbors.iter().enumerate() {
+            for &(u, w) in neighbors.iter() {
+                *maps[community[v]].entry(community[u]).or_insert(0.0) += w;
+            }
+        }
+        Self {
+            neighbors: maps.into_iter().map(|v| v.into_iter().collect()).collect(),
+        }
+    }
+}
+
+/// Renumbers labels from 0 in order of their first appearance. Returns the number of labels
+fn renumber(labels: &mut [usize]) -> usize {
+    let mut id = BTreeMap::new();
+    for label in labels.iter_mut() {
+        let next = id.len();
+        *label = *id.entry(*label).or_insert(next);
+    }
+    id.len()
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Detects communities by Louvain modularity optimization treating edges and weak edges as undirected
+    /// edges of weight 1. See DirectedGraph::louvain_by()
+    pub fn louvain(&self) -> Communities<'_, TEdge> {
+        self.louvain_by(|_| 1.0)
+    }
+
+    /// Detects communities by Louvain modularity optimization treating edges and weak edges as undirected
+    /// edges of non-negative `weight`. Edges in both directions between the same nodes add up, and self loops
+    /// are ignored. Nodes are visited in `Ord` order and ties are broken by the smallest community, so that
+    /// results are deterministic
+    pub fn louvain_by<F: Fn(&TEdge) -> f64>(&self, weight: F) -> Communities<'_, TEdge> {
+        let adjacency = Adjacency::new(self, true);
+        let original = Undirected::new(&adjacency, weight);
+        let mut community: Vec<usize> = (0..original.len()).collect();
+
+        let mut graph = Undirected {
+            neighbors: original.neighbors.clone(),
+        };
+        loop {
+            let degree = graph.degrees();
+            let total: f64 = degree.iter().sum();
+            if total <= 0.0 {
+                break;
+            }
+
+            // Local moving: move each node to the neighboring community of the largest modularity gain
+            let mut local: Vec<usize> = (0..graph.len()).collect();
+            let mut sum = degree.clone();
+            let mut moved = false;
+            loop {
+                let mut changed = false;
+                for v in 0..graph.len() {
+                    let current = local[v];
+                    sum[current] -= degree[v];
+                    let mut weights: BTreeMap<usize, f64> = BTreeMap::new();
+                    weights.insert(current, 0.0);
+                    for &(u, w) in graph.neighbors[v].iter().filter(|(u, _)| *u != v) {
+                        *weights.entry(local[u]).or_insert(0.0) += w;
+                    }
+                    let gain = |c: usize, w: f64| w - sum[c] * degree[v] / total;
+
+                    let mut best = (current, gain(current, weights[&current]));
+                    for (&c, &w) in weights.iter() {
+                        let gain = gain(c, w);
+                        if gain > best.1 + EPSILON {
+                            best = (c, gain);
+                        }
+                    }
+                    sum[best.0] += degree[v];
+                    if best.0 != current {
+                        local[v] = best.0;
+                        changed = true;
+                        moved = true;
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+            if !moved {
+                break;
+            }
+
+            let len = renumber(&mut local);
+            for c in community.iter_mut() {
+                *c = local[*c];
+            }
+            graph = graph.aggregate(&local, len);
+        }
+
+        let len = renumber(&mut community);
+        self.__communities(&adjacency, &original, community, len)
+    }
+
+    /// Detects communities by label propagation treating edges and weak edges as undirected edges of weight 1.
+    /// See DirectedGraph::label_propagation_by()
+    pub fn label_propagation(&self) -> Communities<'_, TEdge> {
+        self.label_propagation_by(|_| 1.0)
+    }
+
+    /// Detects communities by label propagation treating edges and weak edges as undirected edges of `weight`.
+    /// Each node takes the label of the largest total weight among its neighbors, keeping its own label
+    /// on ties if possible, or taking the smallest one. Nodes are updated in `Ord` order until no labels change
+    pub fn label_propagation_by<F: Fn(&TEdge) -> f64>(&self, weight: F) -> Communities<'_, TEdge> {
+        let adjacency = Adjacency::new(self, true);
+        let graph = Undirected::new(&adjacency, weight);
+        let mut label: Vec<usize> = (0..graph.len()).collect();
+        for _ in 0..LABEL_PROPAGATION_MAX_ROUNDS {
+            let mut changed = false;
+            for v in 0..graph.len() {
+                let mut weights: BTreeMap<usize, f64> = BTreeMap::new();
+                for &(u, w) in graph.neighbors[v].iter() {
+                    *weights.entry(label[u]).or_insert(0.0) += w;
+                }
+                let Some(max) = weights.values().copied().reduce(f64::max) else {
+                    continue;
+                };
+                let is_best = |w: f64| w > max - EPSILON;
+                if weights.get(&label[v]).is_some_and(|&w| is_best(w)) {
+                    continue;
+                }
+                if let Some((&best, _)) = weights.iter().find(|(_, &w)| is_best(w)) {
+                    label[v] = best;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let len = renumber(&mut label);
+        self.__communities(&adjacency, &graph, label, len)
+    }
+
+    fn __communities<'a>(
+        &'a self,
+        adjacency: &Adjacency<'a, TEdge>,
+        graph: &Undirected,
+        community: Vec<usize>,
+        len: usize,
+    ) -> Communities<'a, TEdge> {
+        Communities {
+            modularity: graph.modularity(&community, len),
+            community: adjacency.nodes.iter().copied().zip(community).collect(),
+            len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::writer_option::{Cluster, WriterOption};
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (1)---(2)     (5)---(6)
+            | \ / |       | \ / |
+            |  X  |       |  X  |
+            | / \ |       | / \ |
+           (3)---(4) --> (7)---(8)      (9)
+        */
+        for (parent, child) in [
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+            (4, 7),
+            (5, 6),
+            (5, 7),
+            (5, 8),
+            (6, 7),
+            (6, 8),
+            (7, 8),
+        ] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph.add_node(&BasicNode::new(&9));
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_louvain() {
+        let graph = build_graph();
+        let communities = graph.louvain();
+
+        assert_eq!(communities.len(), 3);
+        assert_eq!(
+            communities.members(),
+            vec![vec![&1, &2, &3, &4], vec![&5, &6, &7, &8], vec![&9]]
+        );
+        assert_eq!(communities.community_of(&4), Some(0));
+        assert_eq!(communities.community_of(&7), Some(1));
+        assert_eq!(communities.community_of(&100), None);
+        // 2 * (12 / 26 - (13 / 26)^2)
+        let error = communities.modularity() - 11.0 / 26.0;
+        assert!(
+            -1e-9 < error && error < 1e-9,
+            "{}",
+            communities.modularity()
+        );
+
+        // Heavy bridge pulls (4) and (7) together
+        let communities = graph.louvain_by(|edge| if edge.label() == "4->7" { 100.0 } else { 1.0 });
+        assert_eq!(communities.community_of(&4), communities.community_of(&7));
+    }
+
+    #[test]
+    fn test_directed_graph_label_propagation() {
+        let graph = build_graph();
+        let communities = graph.label_propagation();
+
+        assert_eq!(communities.len(), 3);
+        let members: Vec<usize> = communities.members().iter().map(|v| v.len()).collect();
+        assert_eq!(members, [4, 4, 1]);
+        assert!(communities.modularity() > 0.4);
+    }
+
+    #[test]
+    fn test_directed_graph_dot_write_cluster_by_community() {
+        let graph = build_graph();
+
+        let mut out = Vec::new();
+        let option = WriterOption::new().cluster(Cluster::Community);
+        assert!(graph.dot_write_with_option(&mut out, &option).is_ok());
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("subgraph cluster_").count(), 3, "{}", out);
+        assert!(out.contains("    label=\"0\"\n"), "{}", out);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::edge::Edge;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::graph::union_find_tree::UnionFindTree;
+use crate::node::Node;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Weakly connected components of DirectedGraph. See DirectedGraph::weakly_connected_components()
+pub struct Components<'a, TEdge: Edge> {
+    graph: &'a DirectedGraph<TEdge>,
+    union_find: UnionFindTree<TEdge::Node>,
+    /// Representative --> Members in `Ord` order
+    members: BTreeMap<<TEdge::Node as Node>::NodeIndex, Vec<&'a <TEdge::Node as Node>::NodeIndex>>,
+}
+
+impl<'a, TEdge: Edge> Components<'a, TEdge> {
+    /// Returns number of components
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Returns representative (i.e. the smallest node) of the component containing `node`
+    pub fn representative_of(
+        &self,
+        node: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Option<<TEdge::Node as Node>::NodeIndex> {
+        match self.graph.get_node(node) {
+            Some(_) => Some(self.union_find.find(node)),
+            None => None,
+        }
+    }
+
+    /// Returns members of the component containing `node` in `Ord` order
+    pub fn component_of(
+        &self,
+        node: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Option<&Vec<&'a <TEdge::Node as Node>::NodeIndex>> {
+        self.members.get(&self.representative_of(node)?)
+    }
+
+    /// Returns representatives and members of components in `Ord` order of representatives
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            &<TEdge::Node as Node>::NodeIndex,
+            &Vec<&'a <TEdge::Node as Node>::NodeIndex>,
+        ),
+    > {
+        self.members.iter()
+    }
+
+    /// Returns representatives and sizes of components
+    pub fn sizes(&self) -> BTreeMap<&<TEdge::Node as Node>::NodeIndex, usize> {
+        self.members.iter().map(|(k, v)| (k, v.len())).collect()
+    }
+
+    /// Splits the graph into components. Each graph is named `NAME:REPRESENTATIVE`
+    pub fn graphs(&self) -> Vec<DirectedGraph<TEdge>> {
+        let mut graphs: BTreeMap<&<TEdge::Node as Node>::NodeIndex, DirectedGraph<TEdge>> = self
+            .members
+            .keys()
+            .map(|v| {
+                (
+                    v,
+                    DirectedGraph::new(format!("{}:{}", self.graph.name(), v)),
+                )
+            })
+            .collect();
+        for node in self.graph.nodes() {
+            let representative = self.union_find.find(node.index());
+            if let Some(graph) = graphs.get_mut(&representative) {
+                graph.add_node(node);
+            }
+        }
+        for (edges, weak) in [(self.graph.edges(), false), (self.graph.weak_edges(), true)] {
+            for edge in edges {
+                let representative = self.union_find.find(edge.parent());
+                if let Some(graph) = graphs.get_mut(&representative) {
+                    if weak {
+                        graph.add_weak_edge(edge);
+                    } else {
+                        graph.add_edge(edge);
+                    }
+                }
+            }
+        }
+        graphs.into_values().collect()
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Groups nodes connected by edges or weak edges regardless of their directions
+    pub fn weakly_connected_components(&self) -> Components<'_, TEdge> {
+        let mut union_find = UnionFindTree::new();
+        for edge in self.edges().chain(self.weak_edges()) {
+            union_find.unite(edge.parent(), edge.child());
+        }
+
+        let mut members = BTreeMap::new();
+        for node in self.nodes() {
+            members
+                .entry(union_find.find(node.index()))
+                .or_insert_with(Vec::new)
+                .push(node.index());
+        }
+
+        Components {
+            graph: self,
+            union_find,
+            members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::writer_option::{Cluster, WriterOption};
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (1)     (5)     (7)
+            |       |
+           (3) <-- (4)
+            |
+           (2) ↺
+        */
+        for (parent, child) in [(1, 3), (3, 2), (4, 3), (5, 4), (2, 2)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph.add_node(&BasicNode::new(&7));
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_weakly_connected_components() {
+        let graph = build_graph();
+        let components = graph.weakly_connected_components();
+
+        assert_eq!(components.len(), 2);
+        assert_eq!(components.representative_of(&5), Some(1));
+        assert_eq!(components.representative_of(&7), Some(7));
+        assert_eq!(components.representative_of(&100), None);
+        assert_eq!(components.component_of(&4), Some(&vec![&1, &2, &3, &4, &5]));
+        let sizes: Vec<(usize, usize)> = components
+            .sizes()
+            .into_iter()
+            .map(|(k, v)| (*k, v))
+            .collect();
+        assert_eq!(sizes, [(1, 5), (7, 1)]);
+
+        let graphs = components.graphs();
+        assert_eq!(graphs.len(), 2);
+        assert_eq!(graphs[0].name(), "test:1");
+        assert_eq!(graphs[0].nodes().len(), 5);
+        assert_eq!(graphs[0].edges().len() + graphs[0].weak_edges().len(), 5);
+        assert_eq!(graphs[1].nodes().len(), 1);
+    }
+
+    #[test]
+    fn test_directed_graph_dot_write_cluster_by_component() {
+        let graph = build_graph();
+
+        let mut out = Vec::new();
+        let option = WriterOption::new().cluster(Cluster::Component);
+        assert!(graph.dot_write_with_option(&mut out, &option).is_ok());
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("subgraph cluster_").count(), 2, "{}", out);
+        assert!(out.contains("    label=\"1\"\n"), "{}", out);
+        assert!(out.contains("    label=\"7\"\n"), "{}", out);
+    }
+}
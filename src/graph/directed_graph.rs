@@ -2,6 +2,8 @@ use crate::edge::directed_edge::DirectedEdge;
 use crate::edge::Edge;
 #[allow(unused_imports)]
 use crate::error::GraphError;
+use crate::graph::chain::from_chain_error;
+use crate::graph::writer_option::{Cluster, WriterOption};
 use crate::io;
 use crate::metrics;
 use crate::node::Node;
@@ -10,6 +12,8 @@ use crate::result::Result;
 use alloc::collections::binary_heap::BinaryHeap;
 use alloc::collections::btree_map::Values;
 use alloc::collections::BTreeMap;
+#[allow(unused_imports)]
+use alloc::format;
 use alloc::string::String;
 #[allow(unused_imports)]
 use alloc::vec;
@@ -74,6 +78,10 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
         }
     }
 
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
     pub fn nodes(&self) -> Values<<TEdge::Node as Node>::NodeIndex, TEdge::Node> {
         self.node.values()
     }
@@ -82,6 +90,11 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
         self.edge.values()
     }
 
+    /// Returns weak edges (i.e. self loops and edges making cycles)
+    pub fn weak_edges(&self) -> Values<DirectedEdge<TEdge>, TEdge> {
+        self.weak_edge.values()
+    }
+
     pub fn add_node(&mut self, node: &TEdge::Node) -> () {
         // NOTE: *Last* inserted node overwhelms existing node when nodes with same name are inserted
         self.node.insert(node.index().clone(), node.clone());
@@ -126,6 +139,26 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
             }
         }
 
+        self.__insert_edge(edge);
+    }
+
+    /// Adds `edge` as an edge even if it makes a cycle (i.e. keeps kind of edges copied from another graph).
+    /// Self loops are still weak edges
+    pub(crate) fn add_strong_edge(&mut self, edge: &TEdge) {
+        if edge.parent() == edge.child() {
+            self.add_edge(edge);
+            return;
+        }
+        if self.get_node(&edge.parent()).is_none() {
+            self.add_node(&TEdge::Node::implicit_new(&edge.parent()))
+        }
+        if self.get_node(&edge.child()).is_none() {
+            self.add_node(&TEdge::Node::implicit_new(&edge.child()))
+        }
+        self.__insert_edge(edge);
+    }
+
+    fn __insert_edge(&mut self, edge: &TEdge) {
         self.edge.insert(DirectedEdge::from(&edge), edge.clone());
 
         metrics! {
@@ -162,6 +195,26 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
         self.edge.get(arrow)
     }
 
+    pub fn get_weak_edge(&self, arrow: &DirectedEdge<TEdge>) -> Option<&TEdge> {
+        self.weak_edge.get(arrow)
+    }
+
+    /// Replaces existing edge (or weak edge) having the same parent and child, keeping indexes as they are.
+    /// Returns false if no such edge exists.
+    /// NOTE: DirectedGraph::add_edge() cannot be used for this since the existing edge makes the new one weak
+    pub fn replace_edge(&mut self, edge: &TEdge) -> bool {
+        let arrow = DirectedEdge::from(edge);
+        if let Some(existing) = self.edge.get_mut(&arrow) {
+            *existing = edge.clone();
+            true
+        } else if let Some(existing) = self.weak_edge.get_mut(&arrow) {
+            *existing = edge.clone();
+            true
+        } else {
+            false
+        }
+    }
+
     #[cfg(feature = "metrics")]
     pub fn children_of(
         &self,
@@ -322,11 +375,68 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
         Ok(self.root_of(node)? == node)
     }
 
+    /// Counts nodes of subtree of given node (including given node itself)
+    #[cfg(feature = "metrics")]
+    pub fn subtree_size_of(&self, node: &<TEdge::Node as Node>::NodeIndex) -> Result<usize, TEdge> {
+        if self.get_node(node).is_none() {
+            return Err(GraphError::NodeNotExists(node.clone()));
+        }
+        // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+        let mut visited = HashSet::with_capacity(8);
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(children) = self.children_of(current) {
+                stack.extend(children.iter());
+            }
+        }
+        Ok(visited.len())
+    }
+
+    /// Sorts node indexes by `Ord` of nodes
+    #[cfg(feature = "metrics")]
+    pub(crate) fn sorted_nodes_of<'a, I: Iterator<Item = &'a <TEdge::Node as Node>::NodeIndex>>(
+        &'a self,
+        indexes: I,
+    ) -> Vec<&'a <TEdge::Node as Node>::NodeIndex> {
+        let mut nodes: Vec<(Option<&TEdge::Node>, &<TEdge::Node as Node>::NodeIndex)> =
+            indexes.map(|v| (self.get_node(v), v)).collect();
+        nodes.sort();
+        nodes.into_iter().map(|(_, v)| v).collect()
+    }
+
     pub fn gml_write<T: io::Write>(&self, file: &mut T) -> Result<(), TEdge> {
+        self.gml_write_with_option(file, &WriterOption::default())
+    }
+
+    pub fn gml_write_with_option<T: io::Write>(
+        &self,
+        file: &mut T,
+        option: &WriterOption,
+    ) -> Result<(), TEdge> {
+        match option.compress_chains {
+            Some(min_length) => self
+                .compress_chains(min_length)
+                .0
+                .__gml_write(file, option)
+                .map_err(from_chain_error),
+            None => self.__gml_write(file, option),
+        }
+    }
+
+    // NOTE: Do not compress chains here; compressed graphs are written with this function
+    fn __gml_write<T: io::Write>(&self, file: &mut T, option: &WriterOption) -> Result<(), TEdge> {
         write!(file, "graph [\n")?;
         write!(file, "  directed 1\n")?;
         write!(file, "  name \"{}\"\n", self.name)?;
 
+        let layout = match &option.layout {
+            Some(layout_option) => Some(self.layered_layout(layout_option)?),
+            None => None,
+        };
+
         let mut index_to_id = HashMap::with_capacity(self.node.len());
 
         {
@@ -338,25 +448,68 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
                 write!(file, "  node [\n")?;
                 write!(file, "    id {}\n", id)?;
                 write!(file, "    label \"{}\"\n", node.0)?;
-                // metrics! {{
-                //     write!(file, "    rank {}\n", self.rank_of(index.0)?)?;
-                //     write!(file, "    is_root {}\n", if self.is_root(index.0)? { 1 } else { 0 })?;
-                // }}
+                if option.metrics {
+                    metrics! {{
+                        write!(file, "    rank {}\n", self.rank_of(node.0.index())?)?;
+                        write!(file, "    is_root {}\n", if self.is_root(node.0.index())? { 1 } else { 0 })?;
+                        write!(file, "    subtree_size {}\n", self.subtree_size_of(node.0.index())?)?;
+                        write!(file, "    out_degree {}\n", self.children_of(node.0.index()).map_or(0, |v| v.len()))?;
+                    }}
+                }
+                if let Some(rect) = layout.as_ref().and_then(|v| v.node(node.0.index())) {
+                    write!(file, "    graphics [\n")?;
+                    write!(file, "      x {}\n", rect.x)?;
+                    write!(file, "      y {}\n", rect.y)?;
+                    write!(file, "      w {}\n", rect.width)?;
+                    write!(file, "      h {}\n", rect.height)?;
+                    write!(file, "    ]\n")?;
+                }
                 write!(file, "  ]\n")?;
             }
         }
         {
-            let heap: BinaryHeap<Reverse<&TEdge>> =
-                self.edge.values().map(|v| Reverse(v)).collect();
-            for edge in heap.into_iter_sorted() {
+            let heap: BinaryHeap<Reverse<(&TEdge, bool)>> = self
+                .edge
+                .values()
+                .map(|v| Reverse((v, false)))
+                .chain(
+                    self.weak_edge
+                        .values()
+                        .filter(|_| option.weak_edges)
+                        .map(|v| Reverse((v, true))),
+                )
+                .collect();
+            for Reverse((edge, weak)) in heap.into_iter_sorted() {
                 if let (Some(source), Some(target)) = (
-                    index_to_id.get(edge.0.parent()),
-                    index_to_id.get(edge.0.child()),
+                    index_to_id.get(edge.parent()),
+                    index_to_id.get(edge.child()),
                 ) {
                     write!(file, "  edge [\n")?;
                     write!(file, "    source {}\n", source)?;
                     write!(file, "    target {}\n", target)?;
-                    write!(file, "    label \"{}\"\n", edge.0)?;
+                    write!(file, "    label \"{}\"\n", edge)?;
+                    if weak {
+                        write!(file, "    weak 1\n")?;
+                    }
+                    let route = layout.as_ref().and_then(|v| {
+                        if weak {
+                            v.weak_route_of(&DirectedEdge::from(edge))
+                        } else {
+                            v.route_of(&DirectedEdge::from(edge))
+                        }
+                    });
+                    if let Some(route) = route {
+                        write!(file, "    graphics [\n")?;
+                        write!(file, "      Line [\n")?;
+                        for point in route.points.iter() {
+                            write!(file, "        point [\n")?;
+                            write!(file, "          x {}\n", point.x)?;
+                            write!(file, "          y {}\n", point.y)?;
+                            write!(file, "        ]\n")?;
+                        }
+                        write!(file, "      ]\n")?;
+                        write!(file, "    ]\n")?;
+                    }
                     write!(file, "  ]\n")?;
                 }
             }
@@ -367,7 +520,103 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
     }
 
     pub fn dot_write<T: io::Write>(&self, file: &mut T) -> Result<(), TEdge> {
+        self.dot_write_with_option(file, &WriterOption::default())
+    }
+
+    pub fn dot_write_with_option<T: io::Write>(
+        &self,
+        file: &mut T,
+        option: &WriterOption,
+    ) -> Result<(), TEdge> {
+        match option.compress_chains {
+            Some(min_length) => self
+                .compress_chains(min_length)
+                .0
+                .__dot_write_with_option(file, option)
+                .map_err(from_chain_error),
+            None => self.__dot_write_with_option(file, option),
+        }
+    }
+
+    // NOTE: Do not compress chains here; compressed graphs are written with this function
+    fn __dot_write_with_option<T: io::Write>(
+        &self,
+        file: &mut T,
+        option: &WriterOption,
+    ) -> Result<(), TEdge> {
+        match option.cluster {
+            Cluster::None => self.__dot_write::<T, ()>(file, option, None),
+            #[cfg(feature = "metrics")]
+            Cluster::Root => {
+                let cluster_of = |node: &TEdge::Node| -> Result<_, TEdge> {
+                    let root = self.root_of(node.index())?;
+                    match self.get_node(root) {
+                        Some(root_node) => Ok((root.clone(), format!("{}", root_node))),
+                        None => Err(GraphError::NodeNotExists(root.clone())),
+                    }
+                };
+                self.__dot_write(file, option, Some(&cluster_of))
+            }
+            Cluster::Component => {
+                let components = self.weakly_connected_components();
+                let cluster_of = |node: &TEdge::Node| -> Result<_, TEdge> {
+                    let representative = match components.representative_of(node.index()) {
+                        Some(representative) => representative,
+                        None => return Err(GraphError::NodeNotExists(node.index().clone())),
+                    };
+                    match self.get_node(&representative) {
+                        Some(representative_node) => {
+                            Ok((representative.clone(), format!("{}", representative_node)))
+                        }
+                        None => Err(GraphError::NodeNotExists(representative)),
+                    }
+                };
+                self.__dot_write(file, option, Some(&cluster_of))
+            }
+            Cluster::Community => {
+                let communities = self.louvain();
+                let cluster_of = |node: &TEdge::Node| -> Result<_, TEdge> {
+                    match communities.community_of(node.index()) {
+                        Some(id) => Ok((id, format!("{}", id))),
+                        None => Err(GraphError::NodeNotExists(node.index().clone())),
+                    }
+                };
+                self.__dot_write(file, option, Some(&cluster_of))
+            }
+        }
+    }
+
+    /// Writes DOT grouping nodes into clusters by given key. Each cluster is labeled with its key.
+    /// NOTE: `option.cluster` is ignored
+    pub fn dot_write_clustered_by<T, K, F>(
+        &self,
+        file: &mut T,
+        option: &WriterOption,
+        key: F,
+    ) -> Result<(), TEdge>
+    where
+        T: io::Write,
+        K: Ord + fmt::Display,
+        F: Fn(&TEdge::Node) -> K,
+    {
+        let cluster_of = |node: &TEdge::Node| -> Result<_, TEdge> {
+            let key = key(node);
+            let label = format!("{}", key);
+            Ok((key, label))
+        };
+        self.__dot_write(file, option, Some(&cluster_of))
+    }
+
+    fn __dot_write<T: io::Write, K: Ord>(
+        &self,
+        file: &mut T,
+        option: &WriterOption,
+        cluster_of: Option<&dyn Fn(&TEdge::Node) -> Result<(K, String), TEdge>>,
+    ) -> Result<(), TEdge> {
         write!(file, "digraph {{\n")?;
+        if let Some(rankdir) = option.rankdir {
+            write!(file, "  rankdir={}\n", rankdir)?;
+        }
 
         let mut index_to_id = HashMap::with_capacity(self.node.len());
 
@@ -375,22 +624,58 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
             // Write nodes
             let heap: BinaryHeap<Reverse<&TEdge::Node>> =
                 self.node.values().map(|v| Reverse(v)).collect();
+            let mut clusters: BTreeMap<K, (String, Vec<(usize, &TEdge::Node)>)> = BTreeMap::new();
+            let mut rest = Vec::new();
             for (id, node) in heap.into_iter_sorted().enumerate() {
                 index_to_id.insert(node.0.index(), id);
 
-                write!(file, "  {} [label=\"{}\"]\n", id, node.0)?;
+                match cluster_of {
+                    Some(cluster_of) => {
+                        let (key, label) = cluster_of(node.0)?;
+                        clusters
+                            .entry(key)
+                            .or_insert_with(|| (label, Vec::new()))
+                            .1
+                            .push((id, node.0));
+                    }
+                    None => rest.push((id, node.0)),
+                }
             }
+            for (cluster_id, (label, nodes)) in clusters.values().enumerate() {
+                write!(file, "  subgraph cluster_{} {{\n", cluster_id)?;
+                write!(file, "    label=\"{}\"\n", label)?;
+                self.__dot_write_nodes(file, option, nodes, "    ")?;
+                write!(file, "  }}\n")?;
+            }
+            self.__dot_write_nodes(file, option, &rest, "  ")?;
         }
         {
             // Write edges
-            let heap: BinaryHeap<Reverse<&TEdge>> =
-                self.edge.values().map(|v| Reverse(v)).collect();
-            for edge in heap.into_iter_sorted() {
+            let heap: BinaryHeap<Reverse<(&TEdge, bool)>> = self
+                .edge
+                .values()
+                .map(|v| Reverse((v, false)))
+                .chain(
+                    self.weak_edge
+                        .values()
+                        .filter(|_| option.weak_edges)
+                        .map(|v| Reverse((v, true))),
+                )
+                .collect();
+            for Reverse((edge, weak)) in heap.into_iter_sorted() {
                 if let (Some(source), Some(target)) = (
-                    index_to_id.get(edge.0.parent()),
-                    index_to_id.get(edge.0.child()),
+                    index_to_id.get(edge.parent()),
+                    index_to_id.get(edge.child()),
                 ) {
-                    write!(file, "  {} -> {} [label=\"{}\"]\n", source, target, edge.0)?;
+                    if weak {
+                        write!(
+                            file,
+                            "  {} -> {} [label=\"{}\", style=dashed]\n",
+                            source, target, edge
+                        )?;
+                    } else {
+                        write!(file, "  {} -> {} [label=\"{}\"]\n", source, target, edge)?;
+                    }
                 }
             }
         }
@@ -398,6 +683,47 @@ impl<TEdge: Edge> DirectedGraph<TEdge> {
 
         Ok(())
     }
+
+    #[allow(unused_variables)]
+    fn __dot_write_nodes<T: io::Write>(
+        &self,
+        file: &mut T,
+        option: &WriterOption,
+        nodes: &[(usize, &TEdge::Node)],
+        indent: &str,
+    ) -> Result<(), TEdge> {
+        for (id, node) in nodes {
+            write!(file, "{}{} [label=\"{}\"", indent, id, node)?;
+            if option.metrics {
+                metrics! {{
+                    write!(file, ", rank={}", self.rank_of(node.index())?)?;
+                    write!(file, ", is_root={}", if self.is_root(node.index())? { 1 } else { 0 })?;
+                    write!(file, ", subtree_size={}", self.subtree_size_of(node.index())?)?;
+                    write!(file, ", out_degree={}", self.children_of(node.index()).map_or(0, |v| v.len()))?;
+                }}
+            }
+            write!(file, "]\n")?;
+        }
+        if option.rank_same {
+            metrics! {{
+                let mut ranks: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+                for (id, node) in nodes {
+                    ranks
+                        .entry(self.rank_of(node.index())?)
+                        .or_insert_with(Vec::new)
+                        .push(*id);
+                }
+                for ids in ranks.values().filter(|v| v.len() > 1) {
+                    write!(file, "{}{{rank=same;", indent)?;
+                    for id in ids {
+                        write!(file, " {};", id)?;
+                    }
+                    write!(file, "}}\n")?;
+                }
+            }}
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -408,11 +734,13 @@ mod tests {
     #[allow(unused_imports)]
     use crate::error::GraphError;
     use crate::graph::directed_graph::DirectedGraph;
+    #[allow(unused_imports)]
+    use crate::graph::writer_option::{Cluster, RankDir, WriterOption};
     use crate::metrics;
     use crate::node::basic_node::BasicNode;
-    use crate::node::node_index::NodeIndex;
     use crate::node::Node;
 
+    use alloc::format;
     use alloc::string::String;
     #[allow(unused_imports)]
     use alloc::vec;
@@ -635,7 +963,6 @@ mod tests {
     #[test]
     fn test_directed_graph_real_sample() {
         type Edge = BasicEdge<BasicNode<u64>>;
-        impl NodeIndex for u64 {}
 
         let mut graph = DirectedGraph::new(String::from("test"));
         graph.add_edge(&Edge::new(&0x421493, &0x41c2d9, String::from("1")));
@@ -720,6 +1047,170 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn test_directed_graph_xxx_write_with_option() {
+        let node_1_index = String::from("node_1");
+        let node_2_index = String::from("node_2");
+        let node_3_index = String::from("node_3");
+        let node_4_index = String::from("node_4");
+
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (1) <-+
+           / \   :
+         (2) (3) :
+              |  :
+             (4)-+
+              ↺
+        */
+        graph.add_edge(&TestGraphEdge::new(
+            &node_1_index,
+            &node_2_index,
+            String::from("1->2"),
+        ));
+        graph.add_edge(&TestGraphEdge::new(
+            &node_1_index,
+            &node_3_index,
+            String::from("1->3"),
+        ));
+        graph.add_edge(&TestGraphEdge::new(
+            &node_3_index,
+            &node_4_index,
+            String::from("3->4"),
+        ));
+        graph.add_edge(&TestGraphEdge::new(
+            &node_4_index,
+            &node_1_index,
+            String::from("4->1"),
+        ));
+        graph.add_edge(&TestGraphEdge::new(
+            &node_4_index,
+            &node_4_index,
+            String::from("4->4"),
+        ));
+
+        assert_eq!(graph.subtree_size_of(&node_1_index), Ok(4));
+        assert_eq!(graph.subtree_size_of(&node_3_index), Ok(2));
+
+        let option = WriterOption::new().weak_edges(true).metrics(true);
+
+        {
+            let mut out_gml = io::Cursor::new(Vec::new());
+            assert!(graph.gml_write_with_option(&mut out_gml, &option).is_ok());
+
+            let mut true_file = File::open("tests/test_directed_graph_xxx_write.gml").unwrap();
+            let mut true_gml = Vec::new();
+            assert!(true_file.read_to_end(&mut true_gml).is_ok());
+
+            println!(
+                "{}",
+                Changeset::new(
+                    str::from_utf8(true_gml.as_slice()).unwrap(),
+                    str::from_utf8(out_gml.get_ref()).unwrap(),
+                    ""
+                )
+            );
+
+            assert_eq!(out_gml.get_ref(), &true_gml);
+        }
+
+        {
+            let mut out_dot = io::Cursor::new(Vec::new());
+            assert!(graph.dot_write_with_option(&mut out_dot, &option).is_ok());
+
+            let mut true_file = File::open("tests/test_directed_graph_xxx_write.dot").unwrap();
+            let mut true_dot = Vec::new();
+            assert!(true_file.read_to_end(&mut true_dot).is_ok());
+
+            println!(
+                "{}",
+                Changeset::new(
+                    str::from_utf8(true_dot.as_slice()).unwrap(),
+                    str::from_utf8(out_dot.get_ref()).unwrap(),
+                    ""
+                )
+            );
+
+            assert_eq!(out_dot.get_ref(), &true_dot);
+        }
+    }
+
+    fn build_two_trees_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (1)     (5)
+           / \     |
+         (2) (3)   (6)
+              |
+             (4)
+        */
+        for (parent, child) in [(1, 2), (1, 3), (3, 4), (5, 6)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &format!("node_{}", parent),
+                &format!("node_{}", child),
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    fn assert_dot_eq_file(out_dot: &io::Cursor<Vec<u8>>, path: &str) {
+        let mut true_file = File::open(path).unwrap();
+        let mut true_dot = Vec::new();
+        assert!(true_file.read_to_end(&mut true_dot).is_ok());
+
+        println!(
+            "{}",
+            Changeset::new(
+                str::from_utf8(true_dot.as_slice()).unwrap(),
+                str::from_utf8(out_dot.get_ref()).unwrap(),
+                ""
+            )
+        );
+
+        assert_eq!(out_dot.get_ref(), &true_dot);
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn test_directed_graph_dot_write_cluster_by_root() {
+        let graph = build_two_trees_graph();
+
+        let option = WriterOption::new()
+            .cluster(Cluster::Root)
+            .rankdir(RankDir::LeftToRight)
+            .rank_same(true);
+
+        let mut out_dot = io::Cursor::new(Vec::new());
+        assert!(graph.dot_write_with_option(&mut out_dot, &option).is_ok());
+        assert_dot_eq_file(
+            &out_dot,
+            "tests/test_directed_graph_dot_write_cluster_by_root.dot",
+        );
+    }
+
+    #[test]
+    fn test_directed_graph_dot_write_clustered_by() {
+        let graph = build_two_trees_graph();
+        let node_4_index = String::from("node_4");
+
+        let mut out_dot = io::Cursor::new(Vec::new());
+        assert!(graph
+            .dot_write_clustered_by(&mut out_dot, &WriterOption::new(), |node| {
+                if node.index() < &node_4_index {
+                    "former"
+                } else {
+                    "latter"
+                }
+            })
+            .is_ok());
+        assert_dot_eq_file(
+            &out_dot,
+            "tests/test_directed_graph_dot_write_clustered_by.dot",
+        );
+    }
+
     #[test]
     fn test_directed_graph_multi_root() {
         let node_1_index = String::from("node_1");
@@ -0,0 +1,327 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+/// Immediate dominators and dominance frontiers of nodes reachable from the entry.
+/// See DirectedGraph::dominators() and DirectedGraph::post_dominators()
+pub struct Dominators<'a, TEdge: Edge> {
+    graph: &'a DirectedGraph<TEdge>,
+    nodes: Vec<&'a <TEdge::Node as Node>::NodeIndex>,
+    id: HashMap<&'a <TEdge::Node as Node>::NodeIndex, usize>,
+    entry: usize,
+    /// `None` for unreachable nodes. The entry is dominated by itself
+    idom: Vec<Option<usize>>,
+    frontier: Vec<Vec<usize>>,
+}
+
+impl<'a, TEdge: Edge> Dominators<'a, TEdge> {
+    pub fn entry(&self) -> &'a <TEdge::Node as Node>::NodeIndex {
+        self.nodes[self.entry]
+    }
+
+    /// Returns immediate dominator of `node`. Returns `None` for the entry and unreachable nodes
+    pub fn immediate_dominator_of(
+        &self,
+        node: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Option<&'a <TEdge::Node as Node>::NodeIndex> {
+        let &v = self.id.get(node)?;
+        match self.idom[v] {
+            Some(idom) if v != self.entry => Some(self.nodes[idom]),
+            _ => None,
+        }
+    }
+
+    /// Returns true if every path from the entry to `node` passes through `dominator`.
+    /// A reachable node dominates itself
+    pub fn dominates(
+        &self,
+        dominator: &<TEdge::Node as Node>::NodeIndex,
+        node: &<TEdge::Node as Node>::NodeIndex,
+    ) -> bool {
+        let (Some(&dominator), Some(&node)) = (self.id.get(dominator), self.id.get(node)) else {
+            return false;
+        };
+        if self.idom[dominator].is_none() || self.idom[node].is_none() {
+            return false;
+        }
+        let mut current = node;
+        loop {
+            if current == dominator {
+                return true;
+            }
+            if current == self.entry {
+                return false;
+            }
+            current = self.idom[current].unwrap_or(self.entry);
+        }
+    }
+
+    /// Returns dominance frontier of `node` (i.e. nodes where dominance of `node` ends) in `Ord` order
+    pub fn dominance_frontier_of(
+        &self,
+        node: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Vec<&'a <TEdge::Node as Node>::NodeIndex> {
+        match self.id.get(node) {
+            Some(&v) => self.frontier[v].iter().map(|&v| self.nodes[v]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns dominator tree rooted at the entry as a new graph. Unreachable nodes are not included
+    pub fn tree(&self) -> DirectedGraph<BasicEdge<TEdge::Node>> {
+        let mut tree = DirectedGraph::new(self.graph.name().clone());
+        for (v, idom) in self.idom.iter().enumerate() {
+            if idom.is_some() {
+                if let Some(node) = self.graph.get_node(self.nodes[v]) {
+                    tree.add_node(node);
+                }
+            }
+        }
+        for (v, idom) in self.idom.iter().enumerate() {
+            match idom {
+                Some(idom) if v != self.entry => tree.add_edge(&BasicEdge::new(
+                    self.nodes[*idom],
+                    self.nodes[v],
+                    String::new(),
+                )),
+                _ => (),
+            }
+        }
+        tree
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes dominators of nodes reachable from `entry` following edges and weak edges
+    /// (Cooper, Harvey and Kennedy, "A Simple, Fast Dominance Algorithm")
+    pub fn dominators(
+        &self,
+        entry: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Result<Dominators<'_, TEdge>, TEdge> {
+        self.__dominators(entry, false)
+    }
+
+    /// Computes post-dominators of nodes reaching `exit` (i.e. dominators on reversed graph)
+    pub fn post_dominators(
+        &self,
+        exit: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Result<Dominators<'_, TEdge>, TEdge> {
+        self.__dominators(exit, true)
+    }
+
+    fn __dominators(
+        &self,
+        entry: &<TEdge::Node as Node>::NodeIndex,
+        reverse: bool,
+    ) -> Result<Dominators<'_, TEdge>, TEdge> {
+        let Adjacency {
+            nodes,
+            id,
+            successors,
+            predecessors,
+        } = Adjacency::new(self, true);
+        let (successors, predecessors) = if reverse {
+            (predecessors, successors)
+        } else {
+            (successors, predecessors)
+        };
+        let entry = match id.get(entry) {
+            Some(&entry) => entry,
+            None => return Err(GraphError::NodeNotExists(entry.clone())),
+        };
+
+        // Postorder numbers by DFS from the entry
+        // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+        let mut postorder = Vec::with_capacity(nodes.len());
+        let mut number: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut visited = vec![false; nodes.len()];
+        let mut stack = vec![(entry, 0)];
+        visited[entry] = true;
+        while let Some((v, i)) = stack.pop() {
+            if i < successors[v].len() {
+                stack.push((v, i + 1));
+                let child = successors[v][i].0;
+                if !visited[child] {
+                    visited[child] = true;
+                    stack.push((child, 0));
+                }
+            } else {
+                number[v] = Some(postorder.len());
+                postorder.push(v);
+            }
+        }
+
+        let intersect = |idom: &Vec<Option<usize>>, mut a: usize, mut b: usize| {
+            while a != b {
+                while number[a] < number[b] {
+                    a = idom[a].unwrap_or(entry);
+                }
+                while number[b] < number[a] {
+                    b = idom[b].unwrap_or(entry);
+                }
+            }
+            a
+        };
+
+        let mut idom: Vec<Option<usize>> = vec![None; nodes.len()];
+        idom[entry] = Some(entry);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &v in postorder.iter().rev().filter(|&&v| v != entry) {
+                let mut new_idom = None;
+                for &(p, _) in predecessors[v].iter() {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        Some(new_idom) => intersect(&idom, p, new_idom),
+                        None => p,
+                    });
+                }
+                if new_idom.is_some() && idom[v] != new_idom {
+                    idom[v] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut frontier: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        for &v in postorder.iter() {
+            let reachable: Vec<usize> = predecessors[v]
+                .iter()
+                .map(|v| v.0)
+                .filter(|&p| idom[p].is_some())
+                .collect();
+            if reachable.len() < 2 {
+                continue;
+            }
+            for p in reachable {
+                let mut runner = p;
+                while Some(runner) != idom[v] {
+                    frontier[runner].push(v);
+                    if runner == entry {
+                        break;
+                    }
+                    runner = idom[runner].unwrap_or(entry);
+                }
+            }
+        }
+        for list in frontier.iter_mut() {
+            list.sort();
+            list.dedup();
+        }
+
+        Ok(Dominators {
+            graph: self,
+            nodes,
+            id,
+            entry,
+            idom,
+            frontier,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+            (1)
+             |
+            (2) <---+
+            / \     |
+          (3) (4)   |
+            \ /     |
+            (5) ----+
+             |
+            (6)      (7) --> (5)
+        */
+        for (parent, child) in [
+            (1, 2),
+            (2, 3),
+            (2, 4),
+            (3, 5),
+            (4, 5),
+            (5, 2),
+            (5, 6),
+            (7, 5),
+        ] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_dominators() {
+        let graph = build_graph();
+        let dominators = graph.dominators(&1).unwrap();
+
+        assert_eq!(dominators.entry(), &1);
+        assert_eq!(dominators.immediate_dominator_of(&1), None);
+        assert_eq!(dominators.immediate_dominator_of(&3), Some(&2));
+        assert_eq!(dominators.immediate_dominator_of(&5), Some(&2));
+        assert_eq!(dominators.immediate_dominator_of(&6), Some(&5));
+        // Unreachable from the entry
+        assert_eq!(dominators.immediate_dominator_of(&7), None);
+        assert!(dominators.dominates(&2, &6));
+        assert!(dominators.dominates(&5, &5));
+        assert!(!dominators.dominates(&3, &5));
+        assert!(!dominators.dominates(&7, &5));
+
+        assert_eq!(dominators.dominance_frontier_of(&3), vec![&5]);
+        assert_eq!(dominators.dominance_frontier_of(&5), vec![&2]);
+        assert_eq!(dominators.dominance_frontier_of(&2), vec![&2]);
+        assert_eq!(dominators.dominance_frontier_of(&1), Vec::<&usize>::new());
+
+        let tree = dominators.tree();
+        assert_eq!(tree.nodes().len(), 6);
+        assert_eq!(tree.edges().len(), 5);
+        assert!(tree.get_edge(&DirectedEdge::new(&2, &5)).is_some());
+
+        assert!(matches!(
+            graph.dominators(&100),
+            Err(GraphError::NodeNotExists(100))
+        ));
+    }
+
+    #[test]
+    fn test_directed_graph_post_dominators() {
+        let graph = build_graph();
+        let post_dominators = graph.post_dominators(&6).unwrap();
+
+        assert_eq!(post_dominators.immediate_dominator_of(&3), Some(&5));
+        assert_eq!(post_dominators.immediate_dominator_of(&2), Some(&5));
+        assert_eq!(post_dominators.immediate_dominator_of(&7), Some(&5));
+        assert_eq!(post_dominators.immediate_dominator_of(&1), Some(&2));
+        assert!(post_dominators.dominates(&5, &1));
+        assert_eq!(post_dominators.dominance_frontier_of(&3), vec![&2]);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::edge::directed_edge::DirectedEdge;
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Sub};
+use hashbrown::HashMap;
+
+/// Residual arc. Arcs `2 * i` and `2 * i + 1` are the forward and backward arcs of the i-th edge
+#[derive(Debug, Clone, Copy)]
+struct Arc<W> {
+    to: usize,
+    residual: W,
+}
+
+/// Maximum flow and minimum cut from source to sink. See DirectedGraph::maximum_flow()
+pub struct Flow<'a, TEdge: Edge, W> {
+    nodes: Vec<&'a <TEdge::Node as Node>::NodeIndex>,
+    /// Edges having residual arcs with their capacities
+    edges: Vec<(&'a TEdge, W)>,
+    /// An edge and a weak edge may share the same arrow
+    edge_id: HashMap<DirectedEdge<TEdge>, Vec<usize>>,
+    arcs: Vec<Arc<W>>,
+    /// Nodes reachable from the source on residual arcs
+    source_side: Vec<bool>,
+    value: W,
+}
+
+impl<'a, TEdge: Edge, W: Copy + Add<Output = W> + Sub<Output = W>> Flow<'a, TEdge, W> {
+    /// Returns value of maximum flow (i.e. capacity of minimum cut)
+    pub fn value(&self) -> W {
+        self.value
+    }
+
+    /// Returns flow on the edge (or weak edge) from `arrow.parent()` to `arrow.child()`.
+    /// If both an edge and a weak edge go along `arrow`, returns the sum of flows on them
+    pub fn flow_of(&self, arrow: &DirectedEdge<TEdge>) -> Option<W> {
+        self.edge_id
+            .get(arrow)?
+            .iter()
+            .map(|&i| self.edges[i].1 - self.arcs[2 * i].residual)
+            .reduce(|a, b| a + b)
+    }
+
+    /// Returns edges and flows on them in `Ord` order of edges, including edges of no flow
+    pub fn flows(&self) -> Vec<(&'a TEdge, W)> {
+        let mut flows: Vec<(&'a TEdge, W)> = self
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(i, &(edge, capacity))| (edge, capacity - self.arcs[2 * i].residual))
+            .collect();
+        flows.sort_by(|a, b| a.0.cmp(b.0));
+        flows
+    }
+
+    /// Returns nodes on the source side of minimum cut in `Ord` order
+    pub fn source_side(&self) -> Vec<&'a <TEdge::Node as Node>::NodeIndex> {
+        (0..self.nodes.len())
+            .filter(|&v| self.source_side[v])
+            .map(|v| self.nodes[v])
+            .collect()
+    }
+
+    /// Returns edges from the source side to the sink side (i.e. minimum cut) in `Ord` order
+    pub fn cut_edges(&self) -> Vec<&'a TEdge> {
+        let mut edges: Vec<&'a TEdge> = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let to = self.arcs[2 * i].to;
+                let from = self.arcs[2 * i + 1].to;
+                self.source_side[from] && !self.source_side[to]
+            })
+            .map(|(_, &(edge, _))| edge)
+            .collect();
+        edges.sort();
+        edges
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes maximum flow from `source` to `sink` (Dinic's algorithm) following edges and weak edges
+    /// with capacities given by `capacity`. Self loops are ignored.
+    /// Capacity 1 for every edge gives the number of edge-disjoint paths
+    pub fn maximum_flow<W, F>(
+        &self,
+        source: &<TEdge::Node as Node>::NodeIndex,
+        sink: &<TEdge::Node as Node>::NodeIndex,
+        capacity: F,
+    ) -> Result<Flow<'_, TEdge, W>, TEdge>
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Sub<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        let adjacency = Adjacency::new(self, true);
+        let source = match adjacency.id.get(source) {
+            Some(&v) => v,
+            None => return Err(GraphError::NodeNotExists(source.clone())),
+        };
+        let sink = match adjacency.id.get(sink) {
+            Some(&v) => v,
+            None => return Err(GraphError::NodeNotExists(sink.clone())),
+        };
+
+        let zero = W::default();
+        let n = adjacency.len();
+        let mut edges = Vec::new();
+        let mut edge_id = HashMap::with_capacity(8);
+        let mut arcs = Vec::new();
+        let mut out_arcs: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (v, successors) in adjacency.successors.iter().enumerate() {
+            for &(child, edge) in successors.iter().filter(|(child, _)| *child != v) {
+                let c = capacity(edge);
+                edge_id
+                    .entry(DirectedEdge::from(edge))
+                    .or_insert_with(Vec::new)
+                    .push(edges.len());
+                edges.push((edge, c));
+                out_arcs[v].push(arcs.len());
+                arcs.push(Arc {
+                    to: child,
+                    residual: c,
+                });
+                out_arcs[child].push(arcs.len());
+                arcs.push(Arc {
+                    to: v,
+                    residual: zero,
+                });
+            }
+        }
+
+        // Levels by BFS on residual arcs; `usize::MAX` for unreachable nodes
+        let levels_of = |arcs: &Vec<Arc<W>>| {
+            let mut level = vec![usize::MAX; n];
+            let mut queue = VecDeque::new();
+            level[source] = 0;
+            queue.push_back(source);
+            while let Some(v) = queue.pop_front() {
+                for &a in out_arcs[v].iter() {
+                    let Arc { to, residual } = arcs[a];
+                    if residual > zero && level[to] == usize::MAX {
+                        level[to] = level[v] + 1;
+                        queue.push_back(to);
+                    }
+                }
+            }
+            level
+        };
+
+        let mut value = zero;
+        let mut level = levels_of(&arcs);
+        while source != sink && level[sink] != usize::MAX {
+            // Blocking flow by DFS along levels
+            // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+            let mut next = vec![0; n];
+            let mut path: Vec<usize> = Vec::new();
+            loop {
+                let v = path.last().map_or(source, |&a| arcs[a].to);
+                if v == sink {
+                    let mut bottleneck = arcs[path[0]].residual;
+                    for &a in path.iter() {
+                        if arcs[a].residual < bottleneck {
+                            bottleneck = arcs[a].residual;
+                        }
+                    }
+                    for &a in path.iter() {
+                        arcs[a].residual = arcs[a].residual - bottleneck;
+                        arcs[a ^ 1].residual = arcs[a ^ 1].residual + bottleneck;
+                    }
+                    value = value + bottleneck;
+                    // Retreat to the tail of the first saturated arc
+                    let saturated = path.iter().position(|&a| arcs[a].residual <= zero);
+                    path.truncate(saturated.unwrap_or(0));
+                    continue;
+                }
+
+                let mut advanced = false;
+                while next[v] < out_arcs[v].len() {
+                    let a = out_arcs[v][next[v]];
+                    let Arc { to, residual } = arcs[a];
+                    if residual > zero && level[to] == level[v] + 1 {
+                        path.push(a);
+                        advanced = true;
+                        break;
+                    }
+                    next[v] += 1;
+                }
+                if !advanced {
+                    // Dead end; never visit `v` again in this phase
+                    level[v] = usize::MAX;
+                    match path.pop() {
+                        Some(a) => next[arcs[a ^ 1].to] += 1,
+                        None => break,
+                    }
+                }
+            }
+            level = levels_of(&arcs);
+        }
+
+        Ok(Flow {
+            nodes: adjacency.nodes,
+            edges,
+            edge_id,
+            arcs,
+            source_side: level.into_iter().map(|v| v != usize::MAX).collect(),
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+                 +-10-> (2) -4--+
+                 |       |      v
+           (1) --+       2     (4) -20-> (5)
+                 |       v      ^
+                 +-5--> (3) -6--+
+        */
+        for (parent, child, capacity) in [
+            (1, 2, 10),
+            (1, 3, 5),
+            (2, 3, 2),
+            (2, 4, 4),
+            (3, 4, 6),
+            (4, 5, 20),
+        ] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}", capacity),
+            ));
+        }
+        graph
+    }
+
+    fn capacity(edge: &TestGraphEdge) -> u32 {
+        edge.label().parse().unwrap()
+    }
+
+    #[test]
+    fn test_directed_graph_maximum_flow() {
+        let graph = build_graph();
+        let flow = graph.maximum_flow(&1, &5, capacity).unwrap();
+
+        assert_eq!(flow.value(), 10);
+        assert_eq!(flow.flow_of(&DirectedEdge::new(&2, &4)), Some(4));
+        assert_eq!(flow.flow_of(&DirectedEdge::new(&3, &4)), Some(6));
+        assert_eq!(flow.flow_of(&DirectedEdge::new(&4, &5)), Some(10));
+        assert_eq!(flow.flow_of(&DirectedEdge::new(&1, &5)), None);
+        // Flow conservation at inner nodes
+        for v in [2, 3, 4] {
+            let flows = flow.flows();
+            let inflow: u32 = flows
+                .iter()
+                .filter(|e| e.0.child() == &v)
+                .map(|e| e.1)
+                .sum();
+            let outflow: u32 = flows
+                .iter()
+                .filter(|e| e.0.parent() == &v)
+                .map(|e| e.1)
+                .sum();
+            assert_eq!(inflow, outflow);
+        }
+
+        assert_eq!(flow.source_side(), vec![&1, &2, &3]);
+        let cut: Vec<(usize, usize)> = flow
+            .cut_edges()
+            .iter()
+            .map(|v| (*v.parent(), *v.child()))
+            .collect();
+        assert_eq!(cut, [(2, 4), (3, 4)]);
+
+        // Edge-disjoint paths
+        let flow = graph.maximum_flow(&1, &4, |_| 1).unwrap();
+        assert_eq!(flow.value(), 2);
+
+        let flow = graph.maximum_flow(&5, &1, capacity).unwrap();
+        assert_eq!(flow.value(), 0);
+        assert_eq!(flow.source_side(), vec![&5]);
+        assert!(flow.cut_edges().is_empty());
+
+        assert!(matches!(
+            graph.maximum_flow(&1, &100, capacity),
+            Err(GraphError::NodeNotExists(100))
+        ));
+    }
+
+    #[test]
+    fn test_directed_graph_maximum_flow_parallel_edges() {
+        // (1) =3 and 4=> (2) -10-> (3), where the edge of capacity 4 is a weak edge
+        let mut graph = DirectedGraph::new(String::from("test"));
+        graph.add_edge(&TestGraphEdge::new(&1, &2, String::from("3")));
+        graph.add_weak_edge(&TestGraphEdge::new(&1, &2, String::from("4")));
+        graph.add_edge(&TestGraphEdge::new(&2, &3, String::from("10")));
+
+        let flow = graph.maximum_flow(&1, &3, capacity).unwrap();
+        assert_eq!(flow.value(), 7);
+        assert_eq!(flow.flow_of(&DirectedEdge::new(&1, &2)), Some(7));
+        let flows: Vec<u32> = flow
+            .flows()
+            .iter()
+            .filter(|e| e.0.child() == &2)
+            .map(|e| e.1)
+            .collect();
+        assert_eq!(flows.len(), 2);
+        assert_eq!(flows.iter().sum::<u32>(), 7);
+    }
+}
@@ -0,0 +1,376 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::basic_node::BasicNode;
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub type GeneratedNode = BasicNode<usize>;
+
+/// Edges of generated graphs. Nodes are numbered from 0 in order of their creation
+pub type GeneratedEdge = BasicEdge<GeneratedNode>;
+
+/// Small seeded pseudo random number generator (SplitMix64), so that generated graphs are reproducible
+/// without depending on external crates. NOT for cryptographic use
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`. Returns 0 if `bound` is 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        // NOTE: Multiply-shift instead of modulo to avoid bias to small numbers
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Returns a number in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns true with `probability`
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+}
+
+/// Options for DirectedGraph::random_forest()
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RandomForestOption {
+    /// Number of trees
+    pub trees: usize,
+    /// Depth of the deepest leaf at most. Roots are depth 0
+    pub max_depth: usize,
+    /// Number of children of each node at most
+    pub max_children: usize,
+    /// Number of nodes at most
+    pub max_nodes: usize,
+}
+
+impl Default for RandomForestOption {
+    fn default() -> Self {
+        Self {
+            trees: 1,
+            max_depth: 8,
+            max_children: 3,
+            max_nodes: 1000,
+        }
+    }
+}
+
+impl RandomForestOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trees(mut self, trees: usize) -> Self {
+        self.trees = trees;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn max_children(mut self, max_children: usize) -> Self {
+        self.max_children = max_children;
+        self
+    }
+
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+}
+
+/// Options for DirectedGraph::fuzzing_lineage()
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineageOption {
+    /// Number of seeds (i.e. roots)
+    pub seeds: usize,
+    /// Number of nodes including seeds
+    pub nodes: usize,
+    /// Probability that a new node derives from the latest node, making long chains.
+    /// Otherwise it derives from a node chosen uniformly at random
+    pub chain: f64,
+}
+
+impl Default for LineageOption {
+    fn default() -> Self {
+        Self {
+            seeds: 1,
+            nodes: 1000,
+            chain: 0.8,
+        }
+    }
+}
+
+impl LineageOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seeds(mut self, seeds: usize) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    pub fn nodes(mut self, nodes: usize) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    pub fn chain(mut self, chain: f64) -> Self {
+        self.chain = chain;
+        self
+    }
+}
+
+/// Mutation operators used as edge labels of DirectedGraph::fuzzing_lineage()
+const MUTATIONS: [&str; 6] = ["havoc", "splice", "flip1", "arith8", "int16", "ext_UO"];
+
+impl DirectedGraph<GeneratedEdge> {
+    fn __add_generated_edge(&mut self, parent: usize, child: usize, label: String) {
+        self.add_edge(&GeneratedEdge::new(&parent, &child, label));
+    }
+
+    /// Generates trees by growing each node with random number of children (up to `option.max_children`)
+    /// breadth first, until reaching `option.max_depth` or `option.max_nodes`. Edges have no labels
+    pub fn random_forest(name: String, seed: u64, option: &RandomForestOption) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut graph = Self::new(name);
+        let mut next = 0;
+        let mut queue = VecDeque::new();
+        for _ in 0..option.trees.min(option.max_nodes) {
+            graph.add_node(&GeneratedNode::new(&next));
+            queue.push_back((next, 0));
+            next += 1;
+        }
+        while let Some((parent, depth)) = queue.pop_front() {
+            if depth >= option.max_depth {
+                continue;
+            }
+            for _ in 0..rng.below(option.max_children + 1) {
+                if next >= option.max_nodes {
+                    return graph;
+                }
+                graph.__add_generated_edge(parent, next, String::new());
+                queue.push_back((next, depth + 1));
+                next += 1;
+            }
+        }
+        graph
+    }
+
+    /// Generates Erdős–Rényi random graph G(n, p): each ordered pair of distinct nodes has an edge with `probability`.
+    /// Edges making cycles are weak edges as usual
+    pub fn erdos_renyi(name: String, seed: u64, nodes: usize, probability: f64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut graph = Self::new(name);
+        for v in 0..nodes {
+            graph.add_node(&GeneratedNode::new(&v));
+        }
+        for parent in 0..nodes {
+            for child in (0..nodes).filter(|&v| v != parent) {
+                if rng.chance(probability) {
+                    graph.__add_generated_edge(parent, child, String::new());
+                }
+            }
+        }
+        graph
+    }
+
+    /// Generates random DAG: each pair of nodes `u < v` has an edge `u -> v` with `probability`
+    pub fn random_dag(name: String, seed: u64, nodes: usize, probability: f64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut graph = Self::new(name);
+        for v in 0..nodes {
+            graph.add_node(&GeneratedNode::new(&v));
+        }
+        for parent in 0..nodes {
+            for child in parent + 1..nodes {
+                if rng.chance(probability) {
+                    graph.__add_generated_edge(parent, child, String::new());
+                }
+            }
+        }
+        graph
+    }
+
+    /// Generates Barabási–Albert scale-free graph: starting from `edges_per_node` nodes,
+    /// each new node gets edges from `edges_per_node` distinct existing nodes chosen with probability
+    /// proportional to their degrees (plus one, so that initial nodes can be chosen). Edges go from older nodes
+    /// to newer ones, so that the graph is a DAG
+    pub fn barabasi_albert(name: String, seed: u64, nodes: usize, edges_per_node: usize) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut graph = Self::new(name);
+        // Each node appears once plus once per edge touching it
+        let mut weighted: Vec<usize> = Vec::new();
+        let initial = edges_per_node.max(1).min(nodes);
+        for v in 0..initial {
+            graph.add_node(&GeneratedNode::new(&v));
+            weighted.push(v);
+        }
+        for child in initial..nodes {
+            let mut parents: Vec<usize> = Vec::with_capacity(edges_per_node);
+            while parents.len() < edges_per_node.min(child) {
+                let parent = weighted[rng.below(weighted.len())];
+                if !parents.contains(&parent) {
+                    parents.push(parent);
+                }
+            }
+            parents.sort();
+            graph.add_node(&GeneratedNode::new(&child));
+            weighted.push(child);
+            for parent in parents {
+                graph.__add_generated_edge(parent, child, String::new());
+                weighted.push(parent);
+                weighted.push(child);
+            }
+        }
+        graph
+    }
+
+    /// Generates a lineage resembling fuzzing campaigns (e.g. AFL queues): each new node derives from
+    /// the latest node with probability `option.chain`, otherwise from a random node.
+    /// Edges are labeled with mutation operators such as `havoc`
+    pub fn fuzzing_lineage(name: String, seed: u64, option: &LineageOption) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut graph = Self::new(name);
+        let seeds = option.seeds.max(1).min(option.nodes);
+        for v in 0..seeds {
+            graph.add_node(&GeneratedNode::new(&v));
+        }
+        for child in seeds..option.nodes {
+            let parent = if rng.chance(option.chain) {
+                child - 1
+            } else {
+                rng.below(child)
+            };
+            let label = MUTATIONS[rng.below(MUTATIONS.len())];
+            graph.__add_generated_edge(parent, child, String::from(label));
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::generator::{GeneratedEdge, LineageOption, RandomForestOption, Rng};
+
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn edges_of(graph: &DirectedGraph<GeneratedEdge>) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = graph
+            .edges()
+            .chain(graph.weak_edges())
+            .map(|v| (*v.parent(), *v.child()))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(42);
+        let numbers: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        let mut other = Rng::new(42);
+        assert_eq!(
+            numbers,
+            (0..4).map(|_| other.next_u64()).collect::<Vec<_>>()
+        );
+        assert_ne!(
+            numbers,
+            (0..4).map(|_| Rng::new(43).next_u64()).collect::<Vec<_>>()
+        );
+
+        let mut counts = vec![0; 4];
+        for _ in 0..4000 {
+            counts[rng.below(4)] += 1;
+        }
+        assert!(counts.iter().all(|&v| 800 < v && v < 1200), "{:?}", counts);
+        assert_eq!(rng.below(0), 0);
+        assert!((0..100).all(|_| (0.0..1.0).contains(&rng.next_f64())));
+    }
+
+    #[test]
+    fn test_directed_graph_random_forest() {
+        let option = RandomForestOption::new()
+            .trees(3)
+            .max_depth(4)
+            .max_children(3)
+            .max_nodes(50);
+        let graph = DirectedGraph::random_forest(String::from("forest"), 1, &option);
+        let other = DirectedGraph::random_forest(String::from("forest"), 1, &option);
+
+        assert!(graph.nodes().len() <= 50);
+        assert_eq!(edges_of(&graph), edges_of(&other));
+        // Every node except roots has exactly one parent
+        assert_eq!(graph.edges().len(), graph.nodes().len() - 3);
+        assert_eq!(graph.weak_edges().len(), 0);
+        let path = graph.longest_path().unwrap();
+        assert!(path.length <= 4);
+    }
+
+    #[test]
+    fn test_directed_graph_erdos_renyi() {
+        let graph = DirectedGraph::erdos_renyi(String::from("er"), 1, 10, 1.0);
+        assert_eq!(graph.edges().len() + graph.weak_edges().len(), 10 * 9);
+        let graph = DirectedGraph::erdos_renyi(String::from("er"), 1, 10, 0.0);
+        assert_eq!(graph.nodes().len(), 10);
+        assert_eq!(graph.edges().len() + graph.weak_edges().len(), 0);
+    }
+
+    #[test]
+    fn test_directed_graph_random_dag() {
+        let graph = DirectedGraph::random_dag(String::from("dag"), 7, 30, 0.2);
+        assert_eq!(graph.nodes().len(), 30);
+        assert!(graph.topological_sort().is_ok());
+        assert!(edges_of(&graph).iter().all(|(u, v)| u < v));
+    }
+
+    #[test]
+    fn test_directed_graph_barabasi_albert() {
+        let graph = DirectedGraph::barabasi_albert(String::from("ba"), 3, 100, 2);
+        assert_eq!(graph.nodes().len(), 100);
+        assert_eq!(
+            graph.edges().len() + graph.weak_edges().len(),
+            (100 - 2) * 2
+        );
+        assert!(graph.topological_sort().is_ok());
+    }
+
+    #[test]
+    fn test_directed_graph_fuzzing_lineage() {
+        let option = LineageOption::new().seeds(2).nodes(200).chain(0.9);
+        let graph = DirectedGraph::fuzzing_lineage(String::from("lineage"), 5, &option);
+
+        assert_eq!(graph.nodes().len(), 200);
+        assert_eq!(graph.edges().len(), 198);
+        assert!(graph.edges().all(|v| !v.label().is_empty()));
+        // Long chains
+        assert!(graph.longest_path().unwrap().length > 20);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::edge::Edge;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::io;
+use crate::metrics;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+use hashbrown::HashMap;
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Writes single self-contained HTML file that views this graph interactively.
+    /// * Works offline (i.e. does not load any resources from CDN)
+    /// * Pan with drag, zoom with wheel, search with text box
+    /// * Click node to collapse/expand its subtree (subtrees follow DirectedGraph::parent_of())
+    /// * Hover node to show its `Debug` representation (e.g. metadata of `MetadataNode`)
+    pub fn html_write<T: io::Write>(&self, file: &mut T) -> Result<(), TEdge> {
+        write!(
+            file,
+            "{}",
+            HTML_HEAD.replace("{{TITLE}}", &format!("{}", HtmlEscape(self.name())))
+        )?;
+        self.__html_write_data(file)?;
+        write!(file, "{}", HTML_TAIL)?;
+        Ok(())
+    }
+
+    fn __html_write_data<T: io::Write>(&self, file: &mut T) -> Result<(), TEdge> {
+        let mut index_to_id = HashMap::with_capacity(self.nodes().len());
+        for (id, node) in self.nodes().enumerate() {
+            index_to_id.insert(node.index(), id);
+        }
+
+        // Parent of each node to build collapsible subtrees
+        let mut parent: Vec<Option<usize>> = Vec::with_capacity(index_to_id.len());
+        parent.resize(index_to_id.len(), None);
+        metrics! {{
+            for node in self.nodes() {
+                if let Some(parent_index) = self.parent_of(node.index()) {
+                    parent[index_to_id[node.index()]] = index_to_id.get(parent_index).cloned();
+                }
+            }
+        }}
+        #[cfg(not(feature = "metrics"))]
+        {
+            // NOTE: Without `metrics` feature, the first incoming edge decides parent
+            for edge in self.edges() {
+                let child = index_to_id[edge.child()];
+                if parent[child].is_none() {
+                    parent[child] = index_to_id.get(edge.parent()).cloned();
+                }
+            }
+        }
+
+        write!(
+            file,
+            "const GRAPH = {{\"name\":\"{}\",\"nodes\":[",
+            JsonEscape(self.name())
+        )?;
+        for (id, node) in self.nodes().enumerate() {
+            if id > 0 {
+                write!(file, ",")?;
+            }
+            write!(
+                file,
+                "[\"{}\",\"{}\",{}]",
+                JsonEscape(&format!("{}", node)),
+                JsonEscape(&format!("{:#?}", node)),
+                parent[id].map_or(-1, |v| v as i64)
+            )?;
+        }
+        write!(file, "],\"edges\":[")?;
+        let edges = self
+            .edges()
+            .map(|v| (v, 0))
+            .chain(self.weak_edges().map(|v| (v, 1)));
+        let mut first = true;
+        for (edge, weak) in edges {
+            if let (Some(source), Some(target)) = (
+                index_to_id.get(edge.parent()),
+                index_to_id.get(edge.child()),
+            ) {
+                if !first {
+                    write!(file, ",")?;
+                }
+                first = false;
+                write!(
+                    file,
+                    "[{},{},\"{}\",{}]",
+                    source,
+                    target,
+                    JsonEscape(&format!("{}", edge)),
+                    weak
+                )?;
+            }
+        }
+        write!(file, "]}};\n")?;
+        Ok(())
+    }
+}
+
+/// Escapes string to be embedded in JSON string literal inside `<script>`
+struct JsonEscape<'a>(&'a str);
+
+impl<'a> fmt::Display for JsonEscape<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                // NOTE: Avoid closing <script> tag in the middle of data
+                '<' => write!(f, "\\u003c")?,
+                c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes string to be embedded in HTML (and XML)
+pub(crate) struct HtmlEscape<'a>(pub(crate) &'a str);
+
+impl<'a> fmt::Display for HtmlEscape<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '<' => write!(f, "&lt;")?,
+                '>' => write!(f, "&gt;")?,
+                '&' => write!(f, "&amp;")?,
+                '"' => write!(f, "&quot;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+const HTML_HEAD: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{TITLE}}</title>
+<style>
+html, body { margin: 0; height: 100%; overflow: hidden; font-family: sans-serif; }
+#toolbar { position: fixed; top: 0; left: 0; right: 0; padding: 4px 8px; background: #eee; border-bottom: 1px solid #ccc; }
+#view { position: fixed; top: 33px; left: 0; }
+#tooltip { position: fixed; display: none; padding: 4px; background: #ffe; border: 1px solid #999; white-space: pre; font-family: monospace; font-size: 12px; pointer-events: none; }
+</style>
+</head>
+<body>
+<div id="toolbar">
+<input id="search" placeholder="Search (Enter for next)">
+<button id="fit">Fit</button>
+<button id="expand">Expand all</button>
+<button id="collapse">Collapse all</button>
+<span id="status"></span>
+</div>
+<canvas id="view"></canvas>
+<div id="tooltip"></div>
+<script>
+"##;
+
+const HTML_TAIL: &str = r##"</script>
+<script>
+(function () {
+  "use strict";
+  const DX = 40, DY = 60, R = 6;
+  const N = GRAPH.nodes.length;
+  const parent = new Int32Array(N), children = [];
+  for (let i = 0; i < N; i++) { parent[i] = GRAPH.nodes[i][2]; children.push([]); }
+  for (let i = 0; i < N; i++) if (parent[i] >= 0) children[parent[i]].push(i);
+  {
+    // Nodes on cycles of parent index have no root. Cut the cycles to make them roots
+    const seen = new Uint8Array(N);
+    const mark = (root) => {
+      const stack = [root];
+      seen[root] = 1;
+      while (stack.length > 0) for (const c of children[stack.pop()]) if (!seen[c]) { seen[c] = 1; stack.push(c); }
+    };
+    for (let i = 0; i < N; i++) if (parent[i] < 0) mark(i);
+    for (let i = 0; i < N; i++) {
+      if (seen[i]) continue;
+      children[parent[i]] = children[parent[i]].filter((c) => c !== i);
+      parent[i] = -1;
+      mark(i);
+    }
+  }
+  const collapsed = new Uint8Array(N), visible = new Uint8Array(N);
+  const x = new Float64Array(N), y = new Float64Array(N);
+  const canvas = document.getElementById("view"), ctx = canvas.getContext("2d");
+  const tooltip = document.getElementById("tooltip"), status = document.getElementById("status");
+  let scale = 1, tx = 20, ty = 20, highlighted = -1, lastMatch = -1;
+
+  // Tidy-ish tree layout: leaves are placed left to right, parents are centered above their children
+  function layout() {
+    visible.fill(0);
+    let next = 0;
+    const visit = (root) => {
+      const stack = [[root, 0, 0]];
+      visible[root] = 1;
+      while (stack.length > 0) {
+        const top = stack[stack.length - 1], v = top[0];
+        y[v] = top[1] * DY;
+        const kids = collapsed[v] ? [] : children[v];
+        if (top[2] < kids.length) {
+          const c = kids[top[2]++];
+          if (!visible[c]) { visible[c] = 1; stack.push([c, top[1] + 1, 0]); }
+          continue;
+        }
+        stack.pop();
+        const placed = kids.filter((c) => visible[c]);
+        if (placed.length === 0) x[v] = (next++) * DX;
+        else x[v] = (x[placed[0]] + x[placed[placed.length - 1]]) / 2;
+      }
+    };
+    for (let i = 0; i < N; i++) if (parent[i] < 0) visit(i);
+    let shown = 0;
+    for (let i = 0; i < N; i++) shown += visible[i];
+    status.textContent = shown + " / " + N + " nodes shown";
+  }
+
+  function draw() {
+    canvas.width = window.innerWidth;
+    canvas.height = window.innerHeight - 33;
+    ctx.setTransform(1, 0, 0, 1, 0, 0);
+    ctx.clearRect(0, 0, canvas.width, canvas.height);
+    ctx.setTransform(scale, 0, 0, scale, tx, ty);
+    const x0 = -tx / scale - DX, y0 = -ty / scale - DY;
+    const x1 = (canvas.width - tx) / scale + DX, y1 = (canvas.height - ty) / scale + DY;
+    const inView = (v) => x[v] >= x0 && x[v] <= x1 && y[v] >= y0 && y[v] <= y1;
+    ctx.lineWidth = 1 / scale;
+    for (const e of GRAPH.edges) {
+      const s = e[0], t = e[1];
+      if (!visible[s] || !visible[t] || (!inView(s) && !inView(t))) continue;
+      ctx.strokeStyle = parent[t] === s && !e[3] ? "#666" : "#bbb";
+      ctx.setLineDash(e[3] ? [4 / scale, 4 / scale] : []);
+      ctx.beginPath();
+      ctx.moveTo(x[s], y[s]);
+      ctx.lineTo(x[t], y[t]);
+      ctx.stroke();
+    }
+    ctx.setLineDash([]);
+    ctx.font = 12 + "px sans-serif";
+    for (let v = 0; v < N; v++) {
+      if (!visible[v] || !inView(v)) continue;
+      ctx.fillStyle = v === highlighted ? "#e33" : collapsed[v] && children[v].length > 0 ? "#36c" : "#fff";
+      ctx.strokeStyle = "#333";
+      ctx.beginPath();
+      ctx.arc(x[v], y[v], R, 0, 2 * Math.PI);
+      ctx.fill();
+      ctx.stroke();
+      if (scale > 0.5) {
+        ctx.fillStyle = "#000";
+        let label = GRAPH.nodes[v][0];
+        if (collapsed[v] && children[v].length > 0) label += " (+" + children[v].length + ")";
+        ctx.fillText(label, x[v] + R + 2, y[v] + 4);
+      }
+    }
+  }
+
+  function nodeAt(px, py) {
+    const gx = (px - tx) / scale, gy = (py - ty) / scale, r = Math.max(R, 4 / scale);
+    for (let v = 0; v < N; v++) {
+      if (visible[v] && Math.abs(x[v] - gx) <= r && Math.abs(y[v] - gy) <= r) return v;
+    }
+    return -1;
+  }
+
+  function fit() {
+    let maxX = 0, maxY = 0;
+    for (let v = 0; v < N; v++) if (visible[v]) { maxX = Math.max(maxX, x[v]); maxY = Math.max(maxY, y[v]); }
+    scale = Math.min(2, (window.innerWidth - 40) / (maxX + DX), (window.innerHeight - 73) / (maxY + DY));
+    tx = 20;
+    ty = 20;
+    draw();
+  }
+
+  function focus(v) {
+    for (let p = parent[v]; p >= 0; p = parent[p]) collapsed[p] = 0;
+    layout();
+    highlighted = v;
+    scale = Math.max(scale, 1);
+    tx = canvas.width / 2 - x[v] * scale;
+    ty = canvas.height / 2 - y[v] * scale;
+    draw();
+  }
+
+  let drag = null;
+  canvas.addEventListener("mousedown", (e) => { drag = { x: e.clientX, y: e.clientY, moved: false }; });
+  window.addEventListener("mouseup", (e) => {
+    if (drag && !drag.moved) {
+      const v = nodeAt(e.offsetX, e.offsetY);
+      if (v >= 0 && children[v].length > 0) { collapsed[v] ^= 1; layout(); draw(); }
+    }
+    drag = null;
+  });
+  canvas.addEventListener("mousemove", (e) => {
+    if (drag) {
+      if (Math.abs(e.clientX - drag.x) + Math.abs(e.clientY - drag.y) > 2) drag.moved = true;
+      tx += e.clientX - drag.x;
+      ty += e.clientY - drag.y;
+      drag.x = e.clientX;
+      drag.y = e.clientY;
+      draw();
+      return;
+    }
+    const v = nodeAt(e.offsetX, e.offsetY);
+    if (v >= 0) {
+      tooltip.textContent = GRAPH.nodes[v][1];
+      tooltip.style.left = e.clientX + 12 + "px";
+      tooltip.style.top = e.clientY + 12 + "px";
+      tooltip.style.display = "block";
+    } else {
+      tooltip.style.display = "none";
+    }
+  });
+  canvas.addEventListener("wheel", (e) => {
+    e.preventDefault();
+    const k = e.deltaY < 0 ? 1.2 : 1 / 1.2;
+    tx = e.offsetX - (e.offsetX - tx) * k;
+    ty = e.offsetY - (e.offsetY - ty) * k;
+    scale *= k;
+    draw();
+  }, { passive: false });
+  document.getElementById("search").addEventListener("keydown", (e) => {
+    if (e.key !== "Enter" || e.target.value === "") return;
+    const query = e.target.value.toLowerCase();
+    for (let i = 1; i <= N; i++) {
+      const v = (lastMatch + i) % N;
+      if (GRAPH.nodes[v][0].toLowerCase().includes(query)) { lastMatch = v; focus(v); return; }
+    }
+    status.textContent = "Not found: " + e.target.value;
+  });
+  document.getElementById("fit").addEventListener("click", fit);
+  document.getElementById("expand").addEventListener("click", () => { collapsed.fill(0); layout(); fit(); });
+  document.getElementById("collapse").addEventListener("click", () => {
+    for (let v = 0; v < N; v++) collapsed[v] = parent[v] < 0 && children[v].length > 0 ? 1 : 0;
+    layout();
+    fit();
+  });
+  window.addEventListener("resize", draw);
+  document.title = GRAPH.name;
+  layout();
+  fit();
+})();
+</script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::metadata_node::MetadataNode;
+
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use std::{io, str};
+
+    type TestGraphNode = MetadataNode<String, String>;
+    type TestGraphEdge = BasicEdge<TestGraphNode>;
+
+    #[test]
+    fn test_html_write() {
+        let node_1_index = String::from("node_1");
+        let node_2_index = String::from("node_2");
+
+        let mut graph = DirectedGraph::new(String::from("<test>"));
+        graph.add_node(&TestGraphNode::new(&node_1_index, &String::from("seed")));
+        graph.add_node(&TestGraphNode::new(&node_2_index, &String::from("havoc")));
+        graph.add_edge(&TestGraphEdge::new(
+            &node_1_index,
+            &node_2_index,
+            String::from("1->\"2\""),
+        ));
+        graph.add_edge(&TestGraphEdge::new(
+            &node_2_index,
+            &node_2_index,
+            String::from("2->2"),
+        ));
+
+        let mut out_html = io::Cursor::new(Vec::new());
+        assert!(graph.html_write(&mut out_html).is_ok());
+        let html = str::from_utf8(out_html.get_ref()).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>&lt;test&gt;</title>"));
+        assert!(html.contains(concat!(
+            r#"const GRAPH = {"name":"\u003ctest>","nodes":["#,
+            r#"["seed (node_1)","MetadataNode {\n    index: \"node_1\",\n    metadata: \"seed\",\n}",-1],"#,
+            r#"["havoc (node_2)","MetadataNode {\n    index: \"node_2\",\n    metadata: \"havoc\",\n}",0]],"#,
+            r#""edges":[[0,1,"1->\"2\"",0],[1,1,"2->2",1]]};"#,
+        )));
+        // Self-contained
+        assert!(!html.contains("http://"));
+        assert!(!html.contains("https://"));
+    }
+}
@@ -0,0 +1,424 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+type NodeMatch<'a, TEdge, UEdge> =
+    Box<dyn Fn(&<TEdge as Edge>::Node, &<UEdge as Edge>::Node) -> bool + 'a>;
+type EdgeMatch<'a, TEdge, UEdge> = Box<dyn Fn(&TEdge, &UEdge) -> bool + 'a>;
+
+/// Mapping from pattern nodes to target nodes
+pub type Mapping<'a, TEdge, UEdge> = BTreeMap<
+    &'a <<UEdge as Edge>::Node as Node>::NodeIndex,
+    &'a <<TEdge as Edge>::Node as Node>::NodeIndex,
+>;
+
+struct Side<'a, TEdge: Edge> {
+    adjacency: Adjacency<'a, TEdge>,
+    nodes: Vec<&'a TEdge::Node>,
+    edge: HashMap<(usize, usize), &'a TEdge>,
+}
+
+impl<'a, TEdge: Edge> Side<'a, TEdge> {
+    fn new(graph: &'a DirectedGraph<TEdge>) -> Self {
+        let adjacency = Adjacency::new(graph, true);
+        let nodes = adjacency
+            .nodes
+            .iter()
+            .filter_map(|v| graph.get_node(v))
+            .collect();
+        let mut edge = HashMap::with_capacity(8);
+        for (v, successors) in adjacency.successors.iter().enumerate() {
+            for &(child, e) in successors.iter() {
+                edge.insert((v, child), e);
+            }
+        }
+        Self {
+            adjacency,
+            nodes,
+            edge,
+        }
+    }
+}
+
+/// Iterator over matches of a pattern graph on a target graph found by VF2-style state space search.
+/// See DirectedGraph::isomorphisms() and DirectedGraph::subgraph_isomorphisms()
+pub struct Matches<'a, TEdge: Edge, UEdge: Edge> {
+    target: Side<'a, TEdge>,
+    pattern: Side<'a, UEdge>,
+    node_match: Option<NodeMatch<'a, TEdge, UEdge>>,
+    edge_match: Option<EdgeMatch<'a, TEdge, UEdge>>,
+    /// Also requires that matched target nodes have no edges other than matched ones
+    induced: bool,
+    /// Requires the same numbers of nodes and edges (i.e. isomorphism)
+    exact: bool,
+    /// Pattern nodes in matching order
+    order: Vec<usize>,
+    core_pattern: Vec<Option<usize>>,
+    core_target: Vec<Option<usize>>,
+    /// Candidates and the next candidate to try for each depth
+    stack: Vec<(Vec<usize>, usize)>,
+    started: bool,
+}
+
+impl<'a, TEdge: Edge, UEdge: Edge> Matches<'a, TEdge, UEdge> {
+    fn new(
+        target: &'a DirectedGraph<TEdge>,
+        pattern: &'a DirectedGraph<UEdge>,
+        exact: bool,
+    ) -> Self {
+        let target = Side::new(target);
+        let pattern = Side::new(pattern);
+        let core_pattern = vec![None; pattern.adjacency.len()];
+        let core_target = vec![None; target.adjacency.len()];
+        Self {
+            target,
+            pattern,
+            node_match: None,
+            edge_match: None,
+            induced: true,
+            exact,
+            order: Vec::new(),
+            core_pattern,
+            core_target,
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Matches only nodes satisfying `node_match(target node, pattern node)`
+    pub fn node_match<F: Fn(&TEdge::Node, &UEdge::Node) -> bool + 'a>(
+        mut self,
+        node_match: F,
+    ) -> Self {
+        self.node_match = Some(Box::new(node_match));
+        self
+    }
+
+    /// Matches only edges satisfying `edge_match(target edge, pattern edge)` (e.g. comparing labels)
+    pub fn edge_match<F: Fn(&TEdge, &UEdge) -> bool + 'a>(mut self, edge_match: F) -> Self {
+        self.edge_match = Some(Box::new(edge_match));
+        self
+    }
+
+    /// If false, matched target nodes may have extra edges between them (i.e. monomorphism).
+    /// Default is true (i.e. node-induced subgraph). Ignored for isomorphism
+    pub fn induced(mut self, induced: bool) -> Self {
+        self.induced = induced;
+        self
+    }
+
+    /// Pattern nodes in BFS order ignoring directions, starting from the node of the largest degree
+    fn matching_order(&self) -> Vec<usize> {
+        let adjacency = &self.pattern.adjacency;
+        let degree = |v: usize| adjacency.successors[v].len() + adjacency.predecessors[v].len();
+        let mut starts: Vec<usize> = (0..adjacency.len()).collect();
+        starts.sort_by_key(|&v| core::cmp::Reverse(degree(v)));
+
+        let mut visited = vec![false; adjacency.len()];
+        let mut order = Vec::with_capacity(adjacency.len());
+        for start in starts {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut queue = VecDeque::from([start]);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let successors = adjacency.successors[v].iter();
+                for &(u, _) in successors.chain(adjacency.predecessors[v].iter()) {
+                    if !visited[u] {
+                        visited[u] = true;
+                        queue.push_back(u);
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    /// Target nodes adjacent to the target of a matched neighbor, or all target nodes
+    fn candidates(&self, p: usize) -> Vec<usize> {
+        let pattern = &self.pattern.adjacency;
+        let target = &self.target.adjacency;
+        let mut candidates: Vec<usize> = match (
+            pattern.predecessors[p]
+                .iter()
+                .find_map(|&(q, _)| self.core_pattern[q]),
+            pattern.successors[p]
+                .iter()
+                .find_map(|&(q, _)| self.core_pattern[q]),
+        ) {
+            (Some(t), _) => target.successors[t].iter().map(|v| v.0).collect(),
+            (None, Some(t)) => target.predecessors[t].iter().map(|v| v.0).collect(),
+            (None, None) => (0..target.len()).collect(),
+        };
+        candidates.dedup();
+        candidates
+    }
+
+    fn edge_matches(&self, target: (usize, usize), pattern: (usize, usize)) -> bool {
+        match (
+            self.target.edge.get(&target),
+            self.pattern.edge.get(&pattern),
+        ) {
+            (Some(t), Some(p)) => self.edge_match.as_ref().map_or(true, |f| f(t, p)),
+            _ => false,
+        }
+    }
+
+    fn feasible(&self, p: usize, t: usize) -> bool {
+        if self.core_target[t].is_some() {
+            return false;
+        }
+        let pattern = &self.pattern.adjacency;
+        let target = &self.target.adjacency;
+        let (p_out, p_in) = (pattern.successors[p].len(), pattern.predecessors[p].len());
+        let (t_out, t_in) = (target.successors[t].len(), target.predecessors[t].len());
+        if (self.exact && (p_out != t_out || p_in != t_in)) || p_out > t_out || p_in > t_in {
+            return false;
+        }
+        if let Some(f) = self.node_match.as_ref() {
+            if !f(self.target.nodes[t], self.pattern.nodes[p]) {
+                return false;
+            }
+        }
+
+        let mapped = |q: usize| {
+            if q == p {
+                Some(t)
+            } else {
+                self.core_pattern[q]
+            }
+        };
+        for &(q, _) in pattern.successors[p].iter() {
+            if let Some(u) = mapped(q) {
+                if !self.edge_matches((t, u), (p, q)) {
+                    return false;
+                }
+            }
+        }
+        for &(q, _) in pattern.predecessors[p].iter() {
+            if let Some(u) = mapped(q) {
+                if !self.edge_matches((u, t), (q, p)) {
+                    return false;
+                }
+            }
+        }
+
+        if self.induced || self.exact {
+            let mapped = |u: usize| if u == t { Some(p) } else { self.core_target[u] };
+            for &(u, _) in target.successors[t].iter() {
+                if let Some(q) = mapped(u) {
+                    if !self.pattern.edge.contains_key(&(p, q)) {
+                        return false;
+                    }
+                }
+            }
+            for &(u, _) in target.predecessors[t].iter() {
+                if let Some(q) = mapped(u) {
+                    if !self.pattern.edge.contains_key(&(q, p)) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn mapping(&self) -> Mapping<'a, TEdge, UEdge> {
+        self.order
+            .iter()
+            .filter_map(|&p| {
+                let t = self.core_pattern[p]?;
+                Some((
+                    self.pattern.adjacency.nodes[p],
+                    self.target.adjacency.nodes[t],
+                ))
+            })
+            .collect()
+    }
+}
+
+impl<'a, TEdge: Edge, UEdge: Edge> Iterator for Matches<'a, TEdge, UEdge> {
+    type Item = Mapping<'a, TEdge, UEdge>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            if self.exact
+                && (self.pattern.adjacency.len() != self.target.adjacency.len()
+                    || self.pattern.edge.len() != self.target.edge.len())
+            {
+                return None;
+            }
+            self.order = self.matching_order();
+            if self.order.is_empty() {
+                return Some(BTreeMap::new());
+            }
+            self.stack.push((self.candidates(self.order[0]), 0));
+        }
+
+        // NOTE: Do not recurse; backtracking state is kept in `stack` so that matches are yielded lazily
+        while let Some(depth) = self.stack.len().checked_sub(1) {
+            let p = self.order[depth];
+            if let Some(t) = self.core_pattern[p].take() {
+                self.core_target[t] = None;
+            }
+
+            let mut found = None;
+            while let Some(&t) = self.stack[depth].0.get(self.stack[depth].1) {
+                self.stack[depth].1 += 1;
+                if self.feasible(p, t) {
+                    found = Some(t);
+                    break;
+                }
+            }
+            let Some(t) = found else {
+                self.stack.pop();
+                continue;
+            };
+
+            self.core_pattern[p] = Some(t);
+            self.core_target[t] = Some(p);
+            if depth + 1 == self.order.len() {
+                return Some(self.mapping());
+            }
+            let candidates = self.candidates(self.order[depth + 1]);
+            self.stack.push((candidates, 0));
+        }
+        None
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Iterates isomorphisms from this graph to `other` following edges and weak edges.
+    /// Each match maps every node of this graph to a node of `other`
+    pub fn isomorphisms<'a, UEdge: Edge>(
+        &'a self,
+        other: &'a DirectedGraph<UEdge>,
+    ) -> Matches<'a, UEdge, TEdge> {
+        Matches::new(other, self, true)
+    }
+
+    /// Returns true if this graph and `other` have the same shape regardless of node indexes and labels
+    pub fn is_isomorphic<UEdge: Edge>(&self, other: &DirectedGraph<UEdge>) -> bool {
+        self.isomorphisms(other).next().is_some()
+    }
+
+    /// Iterates occurrences of `pattern` in this graph following edges and weak edges.
+    /// Each match maps every node of `pattern` to a node of this graph.
+    /// Occurrences are node-induced subgraphs unless Matches::induced(false) is given
+    pub fn subgraph_isomorphisms<'a, UEdge: Edge>(
+        &'a self,
+        pattern: &'a DirectedGraph<UEdge>,
+    ) -> Matches<'a, TEdge, UEdge> {
+        Matches::new(self, pattern, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+    use crate::node::Node;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child) in edges {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_isomorphisms() {
+        /*
+           (1)         (10)
+           / \         /  \
+         (2) (3)    (30)  (20)
+          |           |
+         (4)        (40)
+        */
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 4)]);
+        let other = build_graph(&[(10, 20), (10, 30), (30, 40)]);
+
+        let matches: Vec<_> = graph.isomorphisms(&other).collect();
+        assert_eq!(matches.len(), 1);
+        let mapping: Vec<(usize, usize)> = matches[0].iter().map(|(k, v)| (**k, **v)).collect();
+        assert_eq!(mapping, [(1, 10), (2, 30), (3, 20), (4, 40)]);
+        assert!(graph.is_isomorphic(&other));
+
+        // Labels differ
+        assert_eq!(
+            graph
+                .isomorphisms(&other)
+                .edge_match(|a, b| a.label() == b.label())
+                .count(),
+            0
+        );
+        assert!(!graph.is_isomorphic(&build_graph(&[(1, 2), (1, 3), (3, 4), (4, 1)])));
+        assert!(!graph.is_isomorphic(&build_graph(&[(1, 2), (1, 3), (4, 2)])));
+
+        // Automorphisms of a cycle
+        let cycle = build_graph(&[(1, 2), (2, 3), (3, 1)]);
+        assert_eq!(cycle.isomorphisms(&cycle).count(), 3);
+    }
+
+    #[test]
+    fn test_directed_graph_subgraph_isomorphisms() {
+        /*
+           (1)
+           / \
+         (2) (3)
+           \ /
+           (4)
+            |
+           (5)
+        */
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5)]);
+
+        // Path of length 2
+        let pattern = build_graph(&[(1, 2), (2, 3)]);
+        assert_eq!(graph.subgraph_isomorphisms(&pattern).count(), 4);
+
+        // Fork; (2) and (3) have no edge between them
+        let pattern = build_graph(&[(1, 2), (1, 3)]);
+        assert_eq!(graph.subgraph_isomorphisms(&pattern).count(), 2);
+
+        // Fork with a tail; the diamond always has an extra edge to the tail
+        let pattern = build_graph(&[(1, 2), (1, 3), (2, 4)]);
+        assert_eq!(graph.subgraph_isomorphisms(&pattern).count(), 0);
+        assert_eq!(
+            graph.subgraph_isomorphisms(&pattern).induced(false).count(),
+            2
+        );
+
+        // Nodes restricted by index
+        let pattern = build_graph(&[(1, 2), (2, 3)]);
+        let matches: Vec<_> = graph
+            .subgraph_isomorphisms(&pattern)
+            .node_match(|target, _| *target.index() != 1)
+            .collect();
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|v| v.get(&3) == Some(&&5)));
+    }
+}
@@ -0,0 +1,924 @@
+use crate::edge::directed_edge::DirectedEdge;
+use crate::edge::Edge;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::graph::html_writer::HtmlEscape;
+use crate::io;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "metrics"))]
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use hashbrown::HashMap;
+
+/// Approximate width of a character of labels (font size is 12px)
+const CHAR_WIDTH: f64 = 7.0;
+const LABEL_PADDING: f64 = 16.0;
+/// Distance from straight line to control point of curved edges
+const CURVE_OFFSET: f64 = 30.0;
+
+/// Options for DirectedGraph::layered_layout()
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LayoutOption {
+    pub node_height: u32,
+    /// Nodes are widened to fit their labels
+    pub min_node_width: u32,
+    /// Horizontal gap between adjacent nodes in the same layer
+    pub node_spacing: u32,
+    /// Vertical gap between layers
+    pub layer_spacing: u32,
+    pub margin: u32,
+    /// Number of sweeps of crossing minimisation and coordinate assignment
+    pub iterations: usize,
+}
+
+impl Default for LayoutOption {
+    fn default() -> Self {
+        Self {
+            node_height: 24,
+            min_node_width: 40,
+            node_spacing: 20,
+            layer_spacing: 50,
+            margin: 20,
+            iterations: 8,
+        }
+    }
+}
+
+impl LayoutOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn node_height(mut self, height: u32) -> Self {
+        self.node_height = height;
+        self
+    }
+
+    pub fn min_node_width(mut self, width: u32) -> Self {
+        self.min_node_width = width;
+        self
+    }
+
+    pub fn node_spacing(mut self, spacing: u32) -> Self {
+        self.node_spacing = spacing;
+        self
+    }
+
+    pub fn layer_spacing(mut self, spacing: u32) -> Self {
+        self.layer_spacing = spacing;
+        self
+    }
+
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Box of a node. (x, y) is the center
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EdgeRoute {
+    /// From boundary of parent to boundary of child (through bends)
+    pub points: Vec<Point>,
+    /// Edges not going downward along layers (e.g. weak edges) are drawn as a curve
+    pub curved: bool,
+}
+
+/// Result of DirectedGraph::layered_layout()
+#[derive(Debug, Clone)]
+pub struct Layout<TEdge: Edge> {
+    width: f64,
+    height: f64,
+    crossings: usize,
+    node: BTreeMap<<TEdge::Node as Node>::NodeIndex, (usize, Rect)>,
+    edge: BTreeMap<DirectedEdge<TEdge>, EdgeRoute>,
+    weak_edge: BTreeMap<DirectedEdge<TEdge>, EdgeRoute>,
+}
+
+impl<TEdge: Edge> Layout<TEdge> {
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Number of edge crossings between adjacent layers
+    pub fn number_of_crossings(&self) -> usize {
+        self.crossings
+    }
+
+    pub fn node(&self, node: &<TEdge::Node as Node>::NodeIndex) -> Option<&Rect> {
+        self.node.get(node).map(|(_, rect)| rect)
+    }
+
+    pub fn layer_of(&self, node: &<TEdge::Node as Node>::NodeIndex) -> Option<usize> {
+        self.node.get(node).map(|(layer, _)| *layer)
+    }
+
+    pub fn route_of(&self, edge: &DirectedEdge<TEdge>) -> Option<&EdgeRoute> {
+        self.edge.get(edge)
+    }
+
+    pub fn weak_route_of(&self, edge: &DirectedEdge<TEdge>) -> Option<&EdgeRoute> {
+        self.weak_edge.get(edge)
+    }
+}
+
+/// Layered graph including dummy vertices. Vertices `0..number_of_nodes` are real nodes
+struct LayeredGraph {
+    layer: Vec<usize>,
+    width: Vec<f64>,
+    down: Vec<Vec<usize>>,
+    up: Vec<Vec<usize>>,
+    layers: Vec<Vec<usize>>,
+    position: Vec<usize>,
+}
+
+impl LayeredGraph {
+    fn add_vertex(&mut self, layer: usize, width: f64) -> usize {
+        self.layer.push(layer);
+        self.width.push(width);
+        self.down.push(Vec::new());
+        self.up.push(Vec::new());
+        self.layer.len() - 1
+    }
+
+    fn add_link(&mut self, upper: usize, lower: usize) {
+        self.down[upper].push(lower);
+        self.up[lower].push(upper);
+    }
+
+    fn update_position(&mut self) {
+        self.position.resize(self.layer.len(), 0);
+        for vertices in self.layers.iter() {
+            for (i, v) in vertices.iter().enumerate() {
+                self.position[*v] = i;
+            }
+        }
+    }
+
+    /// Counts crossings of links between all adjacent layers
+    fn crossings(&self) -> usize {
+        let mut result = 0;
+        for l in 0..self.layers.len().saturating_sub(1) {
+            let mut links = Vec::new();
+            for u in self.layers[l].iter() {
+                for v in self.down[*u].iter() {
+                    links.push((self.position[*u], self.position[*v]));
+                }
+            }
+            links.sort();
+            // Counts inversions of lower positions with Fenwick tree
+            let size = self.layers[l + 1].len();
+            let mut tree = vec![0usize; size + 1];
+            for (i, (_, lower)) in links.iter().enumerate() {
+                let mut not_greater = 0;
+                let mut k = lower + 1;
+                while k > 0 {
+                    not_greater += tree[k];
+                    k -= k & k.wrapping_neg();
+                }
+                result += i - not_greater;
+                let mut k = lower + 1;
+                while k <= size {
+                    tree[k] += 1;
+                    k += k & k.wrapping_neg();
+                }
+            }
+        }
+        result
+    }
+
+    /// Sorts vertices of given layer by barycenter of their neighbours
+    fn reorder(&mut self, layer: usize, downward: bool) {
+        let mut keys: Vec<(f64, usize)> = Vec::with_capacity(self.layers[layer].len());
+        for v in self.layers[layer].iter() {
+            let neighbours = if downward {
+                &self.up[*v]
+            } else {
+                &self.down[*v]
+            };
+            let key = if neighbours.len() > 0 {
+                neighbours
+                    .iter()
+                    .map(|u| self.position[*u] as f64)
+                    .sum::<f64>()
+                    / neighbours.len() as f64
+            } else {
+                self.position[*v] as f64
+            };
+            keys.push((key, *v));
+        }
+        // NOTE: Stable sort keeps current order of ties
+        keys.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        self.layers[layer] = keys.into_iter().map(|(_, v)| v).collect();
+        for (i, v) in self.layers[layer].iter().enumerate() {
+            self.position[*v] = i;
+        }
+    }
+
+    fn separation(&self, left: usize, right: usize, spacing: f64) -> f64 {
+        (self.width[left] + self.width[right]) / 2.0 + spacing
+    }
+
+    /// Places vertices of given layer close to desired x keeping their order and separation.
+    /// Averages greedy placement from left and from right to balance them
+    fn place(&self, layer: usize, desired: &[f64], spacing: f64) -> Vec<f64> {
+        let vertices = &self.layers[layer];
+        let n = vertices.len();
+        let mut left = Vec::with_capacity(n);
+        for i in 0..n {
+            if i == 0 {
+                left.push(desired[0]);
+            } else {
+                let min = left[i - 1] + self.separation(vertices[i - 1], vertices[i], spacing);
+                left.push(if desired[i] > min { desired[i] } else { min });
+            }
+        }
+        let mut right = vec![0.0; n];
+        for i in (0..n).rev() {
+            if i == n - 1 {
+                right[i] = desired[i];
+            } else {
+                let max = right[i + 1] - self.separation(vertices[i], vertices[i + 1], spacing);
+                right[i] = if desired[i] < max { desired[i] } else { max };
+            }
+        }
+        (0..n).map(|i| (left[i] + right[i]) / 2.0).collect()
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes layered (Sugiyama-style) layout of this graph.
+    /// * Weak edges do not take part in layering; they are what breaks cycles with `metrics` feature.
+    ///   Without `metrics` feature, remaining cycles are broken greedily
+    /// * Layers are DirectedGraph::rank_of() with `metrics` feature, longest path from sources otherwise
+    /// * Crossings are reduced by barycenter heuristic
+    /// * Edges not going downward along layers are routed as curves
+    pub fn layered_layout(&self, option: &LayoutOption) -> Result<Layout<TEdge>, TEdge> {
+        let nodes: Vec<&TEdge::Node> = self.nodes().collect();
+        let n = nodes.len();
+        let mut index_to_id = HashMap::with_capacity(n);
+        for (id, node) in nodes.iter().enumerate() {
+            index_to_id.insert(node.index(), id);
+        }
+        let mut out: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for edge in self.edges() {
+            if let (Some(u), Some(v)) = (
+                index_to_id.get(edge.parent()),
+                index_to_id.get(edge.child()),
+            ) {
+                if u != v {
+                    out[*u].push(*v);
+                }
+            }
+        }
+
+        let layer = self.__layers_of(&nodes, &out)?;
+
+        // Insert dummy vertices to make every link span exactly one layer
+        let mut graph = LayeredGraph {
+            layer: Vec::with_capacity(n),
+            width: Vec::with_capacity(n),
+            down: Vec::with_capacity(n),
+            up: Vec::with_capacity(n),
+            layers: Vec::new(),
+            position: Vec::new(),
+        };
+        for (id, node) in nodes.iter().enumerate() {
+            let width = format!("{}", node).chars().count() as f64 * CHAR_WIDTH + LABEL_PADDING;
+            let min_width = option.min_node_width as f64;
+            graph.add_vertex(layer[id], if width > min_width { width } else { min_width });
+        }
+        let mut chains: Vec<(&TEdge, Vec<usize>)> = Vec::with_capacity(self.edges().len());
+        for edge in self.edges() {
+            if let (Some(u), Some(v)) = (
+                index_to_id.get(edge.parent()),
+                index_to_id.get(edge.child()),
+            ) {
+                let (u, v) = (*u, *v);
+                if layer[v] > layer[u] {
+                    let mut chain = vec![u];
+                    for l in layer[u] + 1..layer[v] {
+                        let dummy = graph.add_vertex(l, 0.0);
+                        graph.add_link(chain[chain.len() - 1], dummy);
+                        chain.push(dummy);
+                    }
+                    graph.add_link(chain[chain.len() - 1], v);
+                    chain.push(v);
+                    chains.push((edge, chain));
+                }
+            }
+        }
+
+        // Initial order: depth first order from sources, which keeps subtrees together
+        let number_of_layers = graph.layer.iter().max().map_or(0, |v| v + 1);
+        graph.layers = vec![Vec::new(); number_of_layers];
+        {
+            let mut starts: Vec<usize> = (0..n).collect();
+            starts.sort_by_key(|v| (layer[*v], *v));
+            let mut visited = vec![false; graph.layer.len()];
+            for start in starts {
+                let mut stack = vec![start];
+                while let Some(v) = stack.pop() {
+                    if visited[v] {
+                        continue;
+                    }
+                    visited[v] = true;
+                    graph.layers[graph.layer[v]].push(v);
+                    stack.extend(graph.down[v].iter().rev().filter(|w| !visited[**w]));
+                }
+            }
+        }
+        graph.update_position();
+
+        // Crossing minimisation
+        let mut best = graph.layers.clone();
+        let mut best_crossings = graph.crossings();
+        for iteration in 0..option.iterations {
+            if best_crossings == 0 {
+                break;
+            }
+            if iteration % 2 == 0 {
+                for l in 1..number_of_layers {
+                    graph.reorder(l, true);
+                }
+            } else {
+                for l in (0..number_of_layers.saturating_sub(1)).rev() {
+                    graph.reorder(l, false);
+                }
+            }
+            let crossings = graph.crossings();
+            if crossings < best_crossings {
+                best = graph.layers.clone();
+                best_crossings = crossings;
+            }
+        }
+        graph.layers = best;
+        graph.update_position();
+
+        // Coordinate assignment
+        let spacing = option.node_spacing as f64;
+        let mut x = vec![0.0; graph.layer.len()];
+        for l in 0..number_of_layers {
+            let desired = vec![0.0; graph.layers[l].len()];
+            for (v, x_v) in graph.layers[l]
+                .iter()
+                .zip(graph.place(l, &desired, spacing))
+            {
+                x[*v] = x_v;
+            }
+        }
+        for iteration in 0..option.iterations {
+            let downward = iteration % 2 == 0;
+            let order: Vec<usize> = if downward {
+                (1..number_of_layers).collect()
+            } else {
+                (0..number_of_layers.saturating_sub(1)).rev().collect()
+            };
+            for l in order {
+                let desired: Vec<f64> = graph.layers[l]
+                    .iter()
+                    .map(|v| {
+                        let neighbours = if downward {
+                            &graph.up[*v]
+                        } else {
+                            &graph.down[*v]
+                        };
+                        if neighbours.len() > 0 {
+                            neighbours.iter().map(|u| x[*u]).sum::<f64>() / neighbours.len() as f64
+                        } else {
+                            x[*v]
+                        }
+                    })
+                    .collect();
+                for (v, x_v) in graph.layers[l]
+                    .iter()
+                    .zip(graph.place(l, &desired, spacing))
+                {
+                    x[*v] = x_v;
+                }
+            }
+        }
+        let margin = option.margin as f64;
+        let left = (0..graph.layer.len())
+            .map(|v| x[v] - graph.width[v] / 2.0)
+            .fold(f64::INFINITY, |a, b| if b < a { b } else { a });
+        for x_v in x.iter_mut() {
+            *x_v += margin - left;
+        }
+        let width = (0..graph.layer.len())
+            .map(|v| x[v] + graph.width[v] / 2.0)
+            .fold(margin, |a, b| if b > a { b } else { a })
+            + margin;
+        let node_height = option.node_height as f64;
+        let layer_height = node_height + option.layer_spacing as f64;
+        let y = |v: usize| margin + graph.layer[v] as f64 * layer_height + node_height / 2.0;
+        let height = if number_of_layers > 0 {
+            margin * 2.0 + number_of_layers as f64 * layer_height - option.layer_spacing as f64
+        } else {
+            margin * 2.0
+        };
+
+        let rect = |v: usize| Rect {
+            x: x[v],
+            y: y(v),
+            width: graph.width[v],
+            height: node_height,
+        };
+        let mut layout = Layout {
+            width,
+            height,
+            crossings: best_crossings,
+            node: BTreeMap::new(),
+            edge: BTreeMap::new(),
+            weak_edge: BTreeMap::new(),
+        };
+        for (id, node) in nodes.iter().enumerate() {
+            layout
+                .node
+                .insert(node.index().clone(), (graph.layer[id], rect(id)));
+        }
+        for (edge, chain) in chains {
+            let mut points = Vec::with_capacity(chain.len());
+            for (i, v) in chain.iter().enumerate() {
+                let y_v = if i == 0 {
+                    y(*v) + node_height / 2.0
+                } else if i == chain.len() - 1 {
+                    y(*v) - node_height / 2.0
+                } else {
+                    y(*v)
+                };
+                points.push(Point { x: x[*v], y: y_v });
+            }
+            layout.edge.insert(
+                DirectedEdge::from(edge),
+                EdgeRoute {
+                    points,
+                    curved: false,
+                },
+            );
+        }
+        for (edges, weak) in [(self.edges(), false), (self.weak_edges(), true)] {
+            for edge in edges {
+                let key = DirectedEdge::from(edge);
+                if !weak && layout.edge.contains_key(&key) {
+                    continue;
+                }
+                if let (Some(u), Some(v)) = (
+                    index_to_id.get(edge.parent()),
+                    index_to_id.get(edge.child()),
+                ) {
+                    let route = EdgeRoute {
+                        points: Self::__curve_endpoints(&rect(*u), &rect(*v)),
+                        curved: true,
+                    };
+                    if weak {
+                        layout.weak_edge.insert(key, route);
+                    } else {
+                        layout.edge.insert(key, route);
+                    }
+                }
+            }
+        }
+        Ok(layout)
+    }
+
+    #[cfg(feature = "metrics")]
+    fn __layers_of(
+        &self,
+        nodes: &[&TEdge::Node],
+        _out: &[Vec<usize>],
+    ) -> Result<Vec<usize>, TEdge> {
+        let mut layer = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            layer.push(self.rank_of(node.index())?);
+        }
+        Ok(layer)
+    }
+
+    /// Longest path layering. Cycles are broken at the smallest unprocessed node
+    #[cfg(not(feature = "metrics"))]
+    fn __layers_of(&self, nodes: &[&TEdge::Node], out: &[Vec<usize>]) -> Result<Vec<usize>, TEdge> {
+        let n = nodes.len();
+        let mut in_degree = vec![0usize; n];
+        for vs in out.iter() {
+            for v in vs {
+                in_degree[*v] += 1;
+            }
+        }
+        let mut layer = vec![0usize; n];
+        let mut queued = vec![false; n];
+        let mut done = vec![false; n];
+        let mut queue = VecDeque::with_capacity(n);
+        for v in 0..n {
+            if in_degree[v] == 0 {
+                queued[v] = true;
+                queue.push_back(v);
+            }
+        }
+        let mut processed = 0;
+        let mut candidate = 0;
+        while processed < n {
+            while let Some(u) = queue.pop_front() {
+                done[u] = true;
+                processed += 1;
+                for v in out[u].iter() {
+                    if done[*v] {
+                        continue;
+                    }
+                    if layer[*v] < layer[u] + 1 {
+                        layer[*v] = layer[u] + 1;
+                    }
+                    if !queued[*v] {
+                        in_degree[*v] -= 1;
+                        if in_degree[*v] == 0 {
+                            queued[*v] = true;
+                            queue.push_back(*v);
+                        }
+                    }
+                }
+            }
+            // Break a cycle
+            while candidate < n && queued[candidate] {
+                candidate += 1;
+            }
+            if candidate < n {
+                queued[candidate] = true;
+                queue.push_back(candidate);
+            }
+        }
+        Ok(layer)
+    }
+
+    fn __curve_endpoints(from: &Rect, to: &Rect) -> Vec<Point> {
+        if from == to {
+            // Self loop on right side
+            let x = from.x + from.width / 2.0;
+            vec![
+                Point {
+                    x,
+                    y: from.y - from.height / 4.0,
+                },
+                Point {
+                    x,
+                    y: from.y + from.height / 4.0,
+                },
+            ]
+        } else if from.y == to.y {
+            let sign = if from.x < to.x { 1.0 } else { -1.0 };
+            vec![
+                Point {
+                    x: from.x + sign * from.width / 2.0,
+                    y: from.y,
+                },
+                Point {
+                    x: to.x - sign * to.width / 2.0,
+                    y: to.y,
+                },
+            ]
+        } else {
+            let sign = if from.y < to.y { 1.0 } else { -1.0 };
+            vec![
+                Point {
+                    x: from.x,
+                    y: from.y + sign * from.height / 2.0,
+                },
+                Point {
+                    x: to.x,
+                    y: to.y - sign * to.height / 2.0,
+                },
+            ]
+        }
+    }
+
+    /// Writes this graph as SVG image using DirectedGraph::layered_layout()
+    pub fn svg_write<T: io::Write>(
+        &self,
+        file: &mut T,
+        option: &LayoutOption,
+    ) -> Result<(), TEdge> {
+        let layout = self.layered_layout(option)?;
+
+        write!(
+            file,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+            layout.width, layout.height, layout.width, layout.height
+        )?;
+        write!(file, "  <title>{}</title>\n", HtmlEscape(self.name()))?;
+        write!(file, "  <defs>\n")?;
+        write!(file, "    <marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\n")?;
+        write!(file, "      <path d=\"M 0 0 L 10 5 L 0 10 z\"/>\n")?;
+        write!(file, "    </marker>\n")?;
+        write!(file, "  </defs>\n")?;
+
+        for (edges, weak) in [(self.edges(), false), (self.weak_edges(), true)] {
+            for edge in edges {
+                let route = match if weak {
+                    layout.weak_route_of(&DirectedEdge::from(edge))
+                } else {
+                    layout.route_of(&DirectedEdge::from(edge))
+                } {
+                    Some(route) => route,
+                    None => continue,
+                };
+                let (path, label_at) = Self::__svg_path(route);
+                write!(
+                    file,
+                    "  <path d=\"{}\" fill=\"none\" stroke=\"black\"{} marker-end=\"url(#arrow)\"/>\n",
+                    path,
+                    if weak { " stroke-dasharray=\"4 4\"" } else { "" }
+                )?;
+                write!(
+                    file,
+                    "  <text x=\"{}\" y=\"{}\" fill=\"#555\">{}</text>\n",
+                    label_at.x + 4.0,
+                    label_at.y,
+                    HtmlEscape(&format!("{}", edge))
+                )?;
+            }
+        }
+        for node in self.nodes() {
+            if let Some(rect) = layout.node(node.index()) {
+                write!(
+                    file,
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"white\" stroke=\"black\"/>\n",
+                    rect.x - rect.width / 2.0,
+                    rect.y - rect.height / 2.0,
+                    rect.width,
+                    rect.height
+                )?;
+                write!(
+                    file,
+                    "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                    rect.x,
+                    rect.y,
+                    HtmlEscape(&format!("{}", node))
+                )?;
+            }
+        }
+        write!(file, "</svg>\n")?;
+        Ok(())
+    }
+
+    /// Returns SVG path data of given route and where to put its label
+    fn __svg_path(route: &EdgeRoute) -> (String, Point) {
+        let points = &route.points;
+        if route.curved && points.len() == 2 {
+            let (p0, p1) = (points[0], points[1]);
+            let (dx, dy) = (p1.x - p0.x, p1.y - p0.y);
+            let length = sqrt(dx * dx + dy * dy);
+            let (nx, ny) = if length > 0.0 {
+                (dy / length, -dx / length)
+            } else {
+                (1.0, 0.0)
+            };
+            let control = Point {
+                x: (p0.x + p1.x) / 2.0 + nx * CURVE_OFFSET,
+                y: (p0.y + p1.y) / 2.0 + ny * CURVE_OFFSET,
+            };
+            let label_at = Point {
+                x: 0.25 * p0.x + 0.5 * control.x + 0.25 * p1.x,
+                y: 0.25 * p0.y + 0.5 * control.y + 0.25 * p1.y,
+            };
+            (
+                format!(
+                    "M {} {} Q {} {} {} {}",
+                    p0.x, p0.y, control.x, control.y, p1.x, p1.y
+                ),
+                label_at,
+            )
+        } else {
+            let mut path = String::new();
+            for (i, p) in points.iter().enumerate() {
+                path.push_str(&format!(
+                    "{}{} {}",
+                    if i == 0 { "M " } else { " L " },
+                    p.x,
+                    p.y
+                ));
+            }
+            let middle = points.len() / 2;
+            let label_at = if points.len() >= 2 {
+                Point {
+                    x: (points[middle - 1].x + points[middle].x) / 2.0,
+                    y: (points[middle - 1].y + points[middle].y) / 2.0,
+                }
+            } else {
+                Point::default()
+            };
+            (path, label_at)
+        }
+    }
+}
+
+/// Square root by Newton's method since `f64::sqrt()` is not available in `no_std`
+fn sqrt(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    let mut x = if value > 1.0 { value } else { 1.0 };
+    for _ in 0..64 {
+        let next = (x + value / x) / 2.0;
+        if next >= x {
+            break;
+        }
+        x = next;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::layout::LayoutOption;
+    use crate::graph::writer_option::WriterOption;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use difference::Changeset;
+    use std::io::Read;
+    use std::{fs::File, io, println, str};
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (parent, child) in edges {
+            graph.add_edge(&TestGraphEdge::new(
+                parent,
+                child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_layered_layout_tree() {
+        /*
+           (1)
+           / \
+         (2) (3)
+              |
+             (4)
+        */
+        let graph = build_graph(&[(1, 2), (1, 3), (3, 4)]);
+        let layout = graph.layered_layout(&LayoutOption::new()).unwrap();
+
+        assert_eq!(layout.layer_of(&1), Some(0));
+        assert_eq!(layout.layer_of(&2), Some(1));
+        assert_eq!(layout.layer_of(&3), Some(1));
+        assert_eq!(layout.layer_of(&4), Some(2));
+        assert_eq!(layout.number_of_crossings(), 0);
+
+        let x = |v: usize| layout.node(&v).unwrap().x;
+        assert!(x(2) < x(3));
+        assert_eq!(x(3), x(4));
+        assert_eq!(x(1), (x(2) + x(3)) / 2.0);
+        // Separated by node width (40) and spacing (20)
+        assert_eq!(x(3) - x(2), 60.0);
+    }
+
+    #[test]
+    fn test_layered_layout_long_edge() {
+        /*
+           (1)
+           / \
+          |  (2)
+          |   |
+          |  (3)
+           \ /
+           (4)
+        */
+        let graph = build_graph(&[(1, 2), (2, 3), (3, 4), (1, 4)]);
+        let layout = graph.layered_layout(&LayoutOption::new()).unwrap();
+
+        let route = layout.route_of(&DirectedEdge::new(&1, &4));
+        #[cfg(not(feature = "metrics"))]
+        {
+            // Bends at dummy nodes in layers 1 and 2
+            assert_eq!(layout.layer_of(&4), Some(3));
+            assert_eq!(route.map(|v| (v.points.len(), v.curved)), Some((4, false)));
+        }
+        #[cfg(feature = "metrics")]
+        {
+            // (1) -> (4) makes a cycle on parent index, so that it is weak edge
+            assert!(route.is_none());
+            assert!(layout.weak_route_of(&DirectedEdge::new(&1, &4)).is_some());
+        }
+    }
+
+    #[test]
+    fn test_layered_layout_reduces_crossings() {
+        /*
+          (1)  (2)
+          / \  /
+        (3) (4)
+        */
+        let graph = build_graph(&[(1, 4), (1, 3), (2, 4)]);
+        let layout = graph.layered_layout(&LayoutOption::new()).unwrap();
+
+        assert_eq!(layout.number_of_crossings(), 0);
+        let x = |v: usize| layout.node(&v).unwrap().x;
+        assert!(x(1) < x(2));
+        assert!(x(3) < x(4));
+    }
+
+    #[test]
+    fn test_layered_layout_cycle() {
+        let graph = build_graph(&[(1, 2), (2, 3), (3, 1), (3, 3)]);
+        let layout = graph.layered_layout(&LayoutOption::new()).unwrap();
+
+        for v in 1..=3 {
+            assert!(layout.node(&v).is_some());
+        }
+        // Self loop
+        assert_eq!(
+            layout
+                .weak_route_of(&DirectedEdge::new(&3, &3))
+                .map(|v| v.curved),
+            Some(true)
+        );
+        // Edge closing the cycle goes upward
+        let route = layout
+            .route_of(&DirectedEdge::new(&3, &1))
+            .or(layout.weak_route_of(&DirectedEdge::new(&3, &1)))
+            .unwrap();
+        assert!(route.curved);
+        assert!(route.points[0].y > route.points[1].y);
+    }
+
+    #[test]
+    fn test_svg_write() {
+        let graph = build_graph(&[(1, 2), (1, 3), (3, 3)]);
+
+        let mut out_svg = io::Cursor::new(Vec::new());
+        assert!(graph.svg_write(&mut out_svg, &LayoutOption::new()).is_ok());
+        let svg = str::from_utf8(out_svg.get_ref()).unwrap();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), 3);
+        assert_eq!(svg.matches("marker-end=\"url(#arrow)\"").count(), 3);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+        assert!(svg.contains(">3-&gt;3</text>"));
+    }
+
+    #[test]
+    fn test_gml_write_with_layout() {
+        let graph = build_graph(&[(1, 2), (1, 3), (3, 4)]);
+
+        let mut out_gml = io::Cursor::new(Vec::new());
+        let option = WriterOption::new().layout(LayoutOption::new());
+        assert!(graph.gml_write_with_option(&mut out_gml, &option).is_ok());
+
+        let mut true_file = File::open("tests/test_gml_write_with_layout.gml").unwrap();
+        let mut true_gml = Vec::new();
+        assert!(true_file.read_to_end(&mut true_gml).is_ok());
+
+        println!(
+            "{}",
+            Changeset::new(
+                str::from_utf8(true_gml.as_slice()).unwrap(),
+                str::from_utf8(out_gml.get_ref()).unwrap(),
+                ""
+            )
+        );
+
+        assert_eq!(out_gml.get_ref(), &true_gml);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Add;
+
+/// Path found by DirectedGraph::longest_path() and DirectedGraph::longest_weighted_path()
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongestPath<'a, TEdge: Edge, W> {
+    /// Nodes from the first to the last. Empty only if the graph is empty
+    pub nodes: Vec<&'a <TEdge::Node as Node>::NodeIndex>,
+    /// Edges between `nodes`, i.e. one fewer than `nodes`
+    pub edges: Vec<&'a TEdge>,
+    /// Number of edges, or the total weight of edges
+    pub length: W,
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Finds the path having the most edges following edges and weak edges.
+    /// Returns GraphError::CycleDetected if the graph is not a DAG
+    pub fn longest_path(&self) -> Result<LongestPath<'_, TEdge, usize>, TEdge> {
+        self.longest_weighted_path(|_| 1)
+    }
+
+    /// Finds the path having the largest total weight (i.e. critical path) following edges and weak edges.
+    /// * Paths may start and end at any node; edges of negative weight are never worth following
+    /// * Among paths of the same weight, the one ending earliest in topological order is taken
+    /// * Returns GraphError::CycleDetected if the graph is not a DAG
+    pub fn longest_weighted_path<W, F>(&self, weight: F) -> Result<LongestPath<'_, TEdge, W>, TEdge>
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        let adjacency = Adjacency::new(self, true);
+        let order = adjacency.topological_order()?;
+
+        let mut length = vec![W::default(); adjacency.len()];
+        let mut previous: Vec<Option<(usize, &TEdge)>> = vec![None; adjacency.len()];
+        let mut last: Option<usize> = None;
+        for &v in order.iter() {
+            for &(parent, edge) in adjacency.predecessors[v].iter() {
+                let candidate = length[parent] + weight(edge);
+                if candidate > length[v] {
+                    length[v] = candidate;
+                    previous[v] = Some((parent, edge));
+                }
+            }
+            if last.map_or(true, |u| length[v] > length[u]) {
+                last = Some(v);
+            }
+        }
+
+        let Some(mut v) = last else {
+            return Ok(LongestPath {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+                length: W::default(),
+            });
+        };
+        let total = length[v];
+        let mut nodes = vec![adjacency.nodes[v]];
+        let mut edges = Vec::new();
+        while let Some((parent, edge)) = previous[v] {
+            nodes.push(adjacency.nodes[parent]);
+            edges.push(edge);
+            v = parent;
+        }
+        nodes.reverse();
+        edges.reverse();
+        Ok(LongestPath {
+            nodes,
+            edges,
+            length: total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::Edge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize, u32)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child, weight) in edges {
+            graph.add_edge(&TestGraphEdge::new(&parent, &child, format!("{}", weight)));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_longest_path() {
+        /*
+           (1) -1-> (2) -1-> (3) -1-> (4)
+            |                          ^
+            +-----------10-------------+
+            |
+            +--1--> (5)
+        */
+        let graph = build_graph(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (1, 4, 10), (1, 5, 1)]);
+
+        let path = graph.longest_path().unwrap();
+        assert_eq!(path.nodes, vec![&1, &2, &3, &4]);
+        assert_eq!(path.length, 3);
+        let labels: Vec<(usize, usize)> = path
+            .edges
+            .iter()
+            .map(|v| (*v.parent(), *v.child()))
+            .collect();
+        assert_eq!(labels, [(1, 2), (2, 3), (3, 4)]);
+
+        let path = graph
+            .longest_weighted_path(|edge| edge.label().parse::<u32>().unwrap())
+            .unwrap();
+        assert_eq!(path.nodes, vec![&1, &4]);
+        assert_eq!(path.edges.len(), 1);
+        assert_eq!(path.length, 10);
+
+        let graph = build_graph(&[]);
+        let path = graph.longest_path().unwrap();
+        assert!(path.nodes.is_empty());
+        assert_eq!(path.length, 0);
+    }
+
+    #[test]
+    fn test_directed_graph_longest_path_cycle() {
+        let graph = build_graph(&[(1, 2, 1), (2, 3, 1), (3, 2, 1)]);
+        assert!(matches!(
+            graph.longest_path(),
+            Err(GraphError::CycleDetected(2 | 3))
+        ));
+    }
+}
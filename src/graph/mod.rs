@@ -1,2 +1,25 @@
+pub(crate) mod adjacency;
+pub mod arborescence;
+pub mod biconnected;
+pub mod centrality;
+pub mod chain;
+pub mod community;
+pub mod component;
 pub mod directed_graph;
+pub mod dominator;
+pub mod flow;
+pub mod generator;
+pub mod html_writer;
+pub mod isomorphism;
+pub mod layout;
+pub mod longest_path;
+pub mod newick;
+pub mod quotient;
+pub mod spanning;
+pub mod structural_hash;
+pub mod transitive;
+pub mod tree_edit;
+#[cfg(feature = "metrics")]
+pub mod tree_writer;
 pub mod union_find_tree;
+pub mod writer_option;
@@ -0,0 +1,488 @@
+use crate::edge::basic_edge::BasicEdge;
+#[allow(unused_imports)]
+use crate::edge::directed_edge::DirectedEdge;
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::io;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::format;
+use alloc::string::String;
+#[allow(unused_imports)]
+use alloc::vec;
+use alloc::vec::Vec;
+#[allow(unused_imports)]
+use core::fmt;
+use core::iter::Peekable;
+use core::str::{CharIndices, FromStr};
+
+/// Characters that cannot appear in unquoted labels
+const NEWICK_SPECIAL_CHARS: &str = "()[]':;,";
+
+/// Characters that need quoting on writing. `_` is allowed in unquoted labels but read as a space
+#[cfg(feature = "metrics")]
+const NEWICK_QUOTED_CHARS: &str = "()[]':;,_";
+
+/// Quotes label only if it is needed
+#[cfg(feature = "metrics")]
+struct NewickLabel<'a>(&'a str);
+
+#[cfg(feature = "metrics")]
+impl<'a> fmt::Display for NewickLabel<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self
+            .0
+            .chars()
+            .any(|c| c.is_whitespace() || NEWICK_QUOTED_CHARS.contains(c))
+        {
+            write!(f, "'{}'", self.0.replace('\'', "''"))
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+enum Step<'a, TIndex> {
+    Open(&'a TIndex),
+    Close(&'a TIndex),
+    Comma,
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Writes each tree of this forest in Newick format (one tree per root and per line).
+    /// * Structure follows DirectedGraph::roots() and DirectedGraph::children_of(),
+    ///   but only the edge to DirectedGraph::parent_of() is followed for nodes having multiple parents
+    /// * Nodes are named with `Display` and quoted if needed (including labels having underscores)
+    /// * Siblings are ordered by `Ord` of nodes
+    #[cfg(feature = "metrics")]
+    pub fn newick_write<T: io::Write>(&self, file: &mut T) -> Result<(), TEdge> {
+        self.newick_write_with_length(file, |_| None::<usize>)
+    }
+
+    /// Same as DirectedGraph::newick_write() but writes branch lengths given by `length` of edges
+    #[cfg(feature = "metrics")]
+    pub fn newick_write_with_length<T, W, F>(&self, file: &mut T, length: F) -> Result<(), TEdge>
+    where
+        T: io::Write,
+        W: fmt::Display,
+        F: Fn(&TEdge) -> Option<W>,
+    {
+        let write_node =
+            |file: &mut T, index: &<TEdge::Node as Node>::NodeIndex| -> Result<(), TEdge> {
+                match self.get_node(index) {
+                    Some(node) => write!(file, "{}", NewickLabel(&format!("{}", node)))?,
+                    None => return Err(GraphError::NodeNotExists(index.clone())),
+                }
+                if let Some(parent) = self.parent_of(index) {
+                    if let Some(w) = self
+                        .get_edge(&DirectedEdge::new(parent, index))
+                        .and_then(|edge| length(edge))
+                    {
+                        write!(file, ":{}", w)?;
+                    }
+                }
+                Ok(())
+            };
+
+        for root in self.sorted_nodes_of(self.roots().into_iter()) {
+            // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+            let mut stack = vec![Step::Open(root)];
+            while let Some(step) = stack.pop() {
+                match step {
+                    Step::Open(node) => {
+                        let children = match self.children_of(node) {
+                            Some(children) => self.sorted_nodes_of(
+                                children
+                                    .iter()
+                                    .filter(|child| self.parent_of(child) == Some(node)),
+                            ),
+                            None => Vec::new(),
+                        };
+                        if children.len() == 0 {
+                            write_node(file, node)?;
+                            continue;
+                        }
+                        write!(file, "(")?;
+                        stack.push(Step::Close(node));
+                        for (i, child) in children.into_iter().enumerate().rev() {
+                            stack.push(Step::Open(child));
+                            if i > 0 {
+                                stack.push(Step::Comma);
+                            }
+                        }
+                    }
+                    Step::Close(node) => {
+                        write!(file, ")")?;
+                        write_node(file, node)?;
+                    }
+                    Step::Comma => write!(file, ",")?,
+                }
+            }
+            write!(file, ";\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl<TNode: Node> DirectedGraph<BasicEdge<TNode>>
+where
+    TNode::NodeIndex: FromStr,
+{
+    /// Reads trees in Newick format. Multiple trees (separated with `;`) make a forest.
+    /// * Labels are parsed into node indexes. Unnamed nodes are indexed with `unnamed(n)` (n = 0, 1, ...)
+    /// * Branch lengths are stored in labels of edges as they are (empty if omitted)
+    /// * Comments (`[...]`) are ignored. Underscores in unquoted labels are read as spaces as the format requires
+    pub fn newick_read<R, F>(
+        name: String,
+        file: &mut R,
+        mut unnamed: F,
+    ) -> Result<Self, BasicEdge<TNode>>
+    where
+        R: io::Read,
+        F: FnMut(usize) -> TNode::NodeIndex,
+    {
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+        let text = match core::str::from_utf8(&buffer) {
+            Ok(text) => text,
+            Err(why) => return Err(GraphError::ParseError(format!("{}", why))),
+        };
+
+        let mut graph = Self::new(name);
+        let mut parser = NewickParser {
+            chars: text.char_indices().peekable(),
+        };
+        let mut number_of_unnamed = 0;
+        let mut new_index = |label: String| -> Result<TNode::NodeIndex, BasicEdge<TNode>> {
+            if label.len() == 0 {
+                number_of_unnamed += 1;
+                Ok(unnamed(number_of_unnamed - 1))
+            } else {
+                match label.parse() {
+                    Ok(index) => Ok(index),
+                    Err(_) => Err(GraphError::ParseError(format!(
+                        "cannot parse node label {:?}",
+                        label
+                    ))),
+                }
+            }
+        };
+
+        loop {
+            parser.skip_blanks()?;
+            if parser.chars.peek().is_none() {
+                break;
+            }
+
+            // Each frame holds (index, branch length) of children of an open parenthesis
+            let mut stack: Vec<Vec<(TNode::NodeIndex, String)>> = Vec::new();
+            let mut closed: Option<Vec<(TNode::NodeIndex, String)>> = None;
+            loop {
+                parser.skip_blanks()?;
+                if let Some((position, '(')) = parser.chars.peek().cloned() {
+                    if closed.is_some() {
+                        return Err(GraphError::ParseError(format!(
+                            "unexpected '(' at {}",
+                            position
+                        )));
+                    }
+                    parser.chars.next();
+                    stack.push(Vec::new());
+                    continue;
+                }
+
+                let index = new_index(parser.label()?)?;
+                parser.skip_blanks()?;
+                let length = match parser.chars.peek() {
+                    Some((_, ':')) => {
+                        parser.chars.next();
+                        parser.skip_blanks()?;
+                        parser.label()?
+                    }
+                    _ => String::new(),
+                };
+                graph.add_node(&TNode::implicit_new(&index));
+                for (child, child_length) in closed.take().unwrap_or_default() {
+                    graph.add_edge(&BasicEdge::new(&index, &child, child_length));
+                }
+
+                parser.skip_blanks()?;
+                match (parser.chars.next(), stack.last_mut()) {
+                    (Some((_, ',')), Some(siblings)) => siblings.push((index, length)),
+                    (Some((_, ')')), Some(_)) => {
+                        let mut siblings = stack.pop().unwrap_or_default();
+                        siblings.push((index, length));
+                        closed = Some(siblings);
+                    }
+                    (Some((_, ';')), None) => break,
+                    (Some((position, c)), _) => {
+                        return Err(GraphError::ParseError(format!(
+                            "unexpected {:?} at {}",
+                            c, position
+                        )))
+                    }
+                    (None, _) => {
+                        return Err(GraphError::ParseError(String::from(
+                            "unexpected end of input (missing ';')",
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+struct NewickParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> NewickParser<'a> {
+    /// Skips whitespaces and comments
+    fn skip_blanks<TNode: Node>(&mut self) -> core::result::Result<(), GraphError<TNode>> {
+        loop {
+            match self.chars.peek() {
+                Some((_, c)) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some((position, '[')) => {
+                    let position = *position;
+                    loop {
+                        match self.chars.next() {
+                            Some((_, ']')) => break,
+                            Some(_) => (),
+                            None => {
+                                return Err(GraphError::ParseError(format!(
+                                    "unterminated comment at {}",
+                                    position
+                                )))
+                            }
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Reads quoted or unquoted label. Returns empty string if there is no label
+    fn label<TNode: Node>(&mut self) -> core::result::Result<String, GraphError<TNode>> {
+        let mut label = String::new();
+        if let Some((position, '\'')) = self.chars.peek().cloned() {
+            self.chars.next();
+            loop {
+                match self.chars.next() {
+                    Some((_, '\'')) => match self.chars.peek() {
+                        Some((_, '\'')) => {
+                            self.chars.next();
+                            label.push('\'');
+                        }
+                        _ => return Ok(label),
+                    },
+                    Some((_, c)) => label.push(c),
+                    None => {
+                        return Err(GraphError::ParseError(format!(
+                            "unterminated quoted label at {}",
+                            position
+                        )))
+                    }
+                }
+            }
+        }
+        while let Some((_, c)) = self.chars.peek() {
+            if c.is_whitespace() || NEWICK_SPECIAL_CHARS.contains(*c) {
+                break;
+            }
+            label.push(if *c == '_' { ' ' } else { *c });
+            self.chars.next();
+        }
+        Ok(label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::Edge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+    use crate::node::Node;
+
+    use alloc::format;
+    use alloc::string::String;
+    #[allow(unused_imports)]
+    use alloc::vec::Vec;
+    use hashbrown::HashSet;
+    #[allow(unused_imports)]
+    use std::{io, str};
+
+    type TestGraphNode = BasicNode<String>;
+    type TestGraphEdge = BasicEdge<TestGraphNode>;
+
+    fn read(text: &str) -> crate::result::Result<DirectedGraph<TestGraphEdge>, TestGraphEdge> {
+        DirectedGraph::newick_read(
+            String::from("test"),
+            &mut io::Cursor::new(text.as_bytes()),
+            |n| format!("#{}", n),
+        )
+    }
+
+    fn edges_of(graph: &DirectedGraph<TestGraphEdge>) -> HashSet<(String, String, String)> {
+        graph
+            .edges()
+            .map(|edge| {
+                (
+                    edge.parent().clone(),
+                    edge.child().clone(),
+                    edge.label().clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_newick_read() {
+        let graph =
+            read("(A:0.1,B:0.2,(C:0.3,'D E':0.4)F:0.5)G; ('it''s',[comment] H)I;\n").unwrap();
+
+        assert_eq!(
+            graph
+                .nodes()
+                .map(|v| v.index().clone())
+                .collect::<HashSet<String>>(),
+            ["A", "B", "C", "D E", "F", "G", "it's", "H", "I"]
+                .iter()
+                .map(|v| String::from(*v))
+                .collect()
+        );
+        assert_eq!(
+            edges_of(&graph),
+            [
+                ("G", "A", "0.1"),
+                ("G", "B", "0.2"),
+                ("G", "F", "0.5"),
+                ("F", "C", "0.3"),
+                ("F", "D E", "0.4"),
+                ("I", "it's", ""),
+                ("I", "H", ""),
+            ]
+            .iter()
+            .map(|(p, c, l)| (String::from(*p), String::from(*c), String::from(*l)))
+            .collect()
+        );
+    }
+
+    #[test]
+    fn test_newick_read_unnamed() {
+        let graph = read("((A,B),C);").unwrap();
+
+        assert_eq!(
+            edges_of(&graph),
+            [
+                ("#0", "A", ""),
+                ("#0", "B", ""),
+                ("#1", "#0", ""),
+                ("#1", "C", ""),
+            ]
+            .iter()
+            .map(|(p, c, l)| (String::from(*p), String::from(*c), String::from(*l)))
+            .collect()
+        );
+    }
+
+    #[test]
+    fn test_newick_read_error() {
+        assert!(matches!(read("(A,B"), Err(GraphError::ParseError(_))));
+        assert!(matches!(read("(A,B));"), Err(GraphError::ParseError(_))));
+        assert!(matches!(read("(A,B)C"), Err(GraphError::ParseError(_))));
+        assert!(matches!(read("('A,B)C;"), Err(GraphError::ParseError(_))));
+
+        let graph = DirectedGraph::<BasicEdge<BasicNode<usize>>>::newick_read(
+            String::from("test"),
+            &mut io::Cursor::new("(1,x)2;".as_bytes()),
+            |n| n,
+        );
+        assert!(matches!(graph, Err(GraphError::ParseError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn test_newick_write() {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+            (root)         (x y)
+            /    \           |
+          (b)    (a)       (it's)
+                  |
+                 (c)
+        */
+        for (parent, child, length) in [
+            ("root", "b", "1"),
+            ("root", "a", "2"),
+            ("a", "c", "3"),
+            ("x y", "it's", "4"),
+        ] {
+            graph.add_edge(&TestGraphEdge::new(
+                &String::from(parent),
+                &String::from(child),
+                String::from(length),
+            ));
+        }
+
+        let mut out = io::Cursor::new(Vec::new());
+        assert!(graph.newick_write(&mut out).is_ok());
+        assert_eq!(
+            str::from_utf8(out.get_ref()).unwrap(),
+            "((c)a,b)root;\n('it''s')'x y';\n"
+        );
+
+        let mut out = io::Cursor::new(Vec::new());
+        assert!(graph
+            .newick_write_with_length(&mut out, |edge| Some(edge.label().clone()))
+            .is_ok());
+        let text = str::from_utf8(out.get_ref()).unwrap();
+        assert_eq!(text, "((c:3)a:2,b:1)root;\n('it''s':4)'x y';\n");
+
+        // Round trip
+        assert_eq!(edges_of(&read(text).unwrap()), edges_of(&graph));
+    }
+
+    #[test]
+    fn test_newick_read_underscore() {
+        let graph = read("(ext_UO,'queue_000')A_B;").unwrap();
+        assert_eq!(
+            edges_of(&graph),
+            [("A B", "ext UO", ""), ("A B", "queue_000", "")]
+                .iter()
+                .map(|(p, c, l)| (String::from(*p), String::from(*c), String::from(*l)))
+                .collect()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn test_newick_write_underscore() {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (parent, child) in [("queue_000", "ext_UO"), ("queue_000", "havoc")] {
+            graph.add_edge(&TestGraphEdge::new(
+                &String::from(parent),
+                &String::from(child),
+                String::new(),
+            ));
+        }
+
+        let mut out = io::Cursor::new(Vec::new());
+        assert!(graph.newick_write(&mut out).is_ok());
+        let text = str::from_utf8(out.get_ref()).unwrap();
+        assert_eq!(text, "('ext_UO',havoc)'queue_000';\n");
+
+        // Round trip
+        assert_eq!(edges_of(&read(text).unwrap()), edges_of(&graph));
+    }
+}
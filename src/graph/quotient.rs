@@ -0,0 +1,126 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::edge::Edge;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::basic_node::BasicNode;
+use crate::node::node_index::NodeIndex;
+use crate::node::Node;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Maps each node of original graph to its merged node. See DirectedGraph::quotient()
+pub type QuotientMap<TIndex, K> = BTreeMap<TIndex, K>;
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Merges nodes sharing the same `key` into one node (i.e. quotient graph) and returns it with the mapping from original nodes.
+    /// * Edges (including weak edges) between the same pair of merged nodes are merged into one edge labeled with `combine`,
+    ///   which receives the original edges in `Ord` order
+    /// * Edges inside a merged node become self loops (i.e. weak edges)
+    /// * Cycles made by merging are resolved by DirectedGraph::add_edge() as usual
+    pub fn quotient<K, F, C>(
+        &self,
+        key: F,
+        combine: C,
+    ) -> (
+        DirectedGraph<BasicEdge<BasicNode<K>>>,
+        QuotientMap<<TEdge::Node as Node>::NodeIndex, K>,
+    )
+    where
+        K: NodeIndex,
+        F: Fn(&TEdge::Node) -> K,
+        C: Fn(&[&TEdge]) -> String,
+    {
+        let mut mapping = QuotientMap::new();
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            let merged = key(node);
+            graph.add_node(&BasicNode::new(&merged));
+            mapping.insert(node.index().clone(), merged);
+        }
+
+        let mut parallel_edges: BTreeMap<(&K, &K), Vec<&TEdge>> = BTreeMap::new();
+        for edge in self.edges().chain(self.weak_edges()) {
+            if let (Some(parent), Some(child)) =
+                (mapping.get(edge.parent()), mapping.get(edge.child()))
+            {
+                parallel_edges
+                    .entry((parent, child))
+                    .or_insert_with(Vec::new)
+                    .push(edge);
+            }
+        }
+        for ((parent, child), mut edges) in parallel_edges {
+            edges.sort();
+            graph.add_edge(&BasicEdge::new(parent, child, combine(&edges)));
+        }
+
+        (graph, mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+    use crate::node::Node;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    #[test]
+    fn test_directed_graph_quotient() {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (10)     (20)
+           /  \      |
+         (11) (12)  (21)
+           \   /
+            (22)
+        */
+        for (parent, child) in [(10, 11), (10, 12), (11, 22), (12, 22), (20, 21)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}", parent + child),
+            ));
+        }
+
+        // Groups by tens
+        let (quotient, mapping) = graph.quotient(
+            |node| *node.index() / 10,
+            |edges| {
+                let sum: usize = edges
+                    .iter()
+                    .map(|v| v.label().parse::<usize>().unwrap())
+                    .sum();
+                format!("{}", sum)
+            },
+        );
+
+        assert_eq!(quotient.nodes().len(), 2);
+        assert_eq!(mapping.get(&12), Some(&1));
+        assert_eq!(mapping.get(&22), Some(&2));
+        assert_eq!(
+            quotient
+                .get_edge(&DirectedEdge::new(&1, &2))
+                .map(|v| v.label().clone()),
+            Some(format!("{}", 33 + 34))
+        );
+        // Edges inside merged nodes become self loops
+        let self_loops: Vec<_> = quotient
+            .weak_edges()
+            .map(|v| (*v.parent(), *v.child(), v.label().clone()))
+            .collect();
+        assert_eq!(
+            self_loops,
+            [(1, 1, format!("{}", 21 + 22)), (2, 2, format!("{}", 41))]
+        );
+    }
+}
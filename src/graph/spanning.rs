@@ -0,0 +1,129 @@
+use crate::edge::Edge;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::graph::union_find_tree::UnionFindTree;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::Add;
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes minimum spanning forest (Kruskal's algorithm) treating edges and weak edges as undirected.
+    /// Returns a new graph having all nodes and selected edges, and the total weight of selected edges.
+    /// Edges of the same weight are taken in `Ord` order
+    pub fn minimum_spanning_forest<W, F>(&self, weight: F) -> (DirectedGraph<TEdge>, W)
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        self.__spanning_forest(weight, false)
+    }
+
+    /// Computes maximum spanning forest. See DirectedGraph::minimum_spanning_forest()
+    pub fn maximum_spanning_forest<W, F>(&self, weight: F) -> (DirectedGraph<TEdge>, W)
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        self.__spanning_forest(weight, true)
+    }
+
+    fn __spanning_forest<W, F>(&self, weight: F, maximum: bool) -> (DirectedGraph<TEdge>, W)
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        F: Fn(&TEdge) -> W,
+    {
+        let mut edges: Vec<(W, &TEdge)> = self
+            .edges()
+            .chain(self.weak_edges())
+            .map(|v| (weight(v), v))
+            .collect();
+        edges.sort_by(|(w, e), (v, f)| {
+            // NOTE: Incomparable weights (e.g. NaN) are treated as equal
+            let order = w.partial_cmp(v).unwrap_or(Ordering::Equal);
+            let order = if maximum { order.reverse() } else { order };
+            order.then_with(|| e.cmp(f))
+        });
+
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            graph.add_node(node);
+        }
+
+        let mut union_find = UnionFindTree::<TEdge::Node>::new();
+        let mut total = W::default();
+        for (w, edge) in edges {
+            if union_find.same(edge.parent(), edge.child()) {
+                continue;
+            }
+            union_find.unite(edge.parent(), edge.child());
+            graph.add_edge(edge);
+            total = total + w;
+        }
+        (graph, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (1) -4-> (2)
+            |      / |
+            1    2   5
+            v  v     v
+           (3) -3-> (4)      (5) -7-> (6)
+        */
+        for (parent, child, weight) in [
+            (1, 2, 4),
+            (1, 3, 1),
+            (2, 3, 2),
+            (2, 4, 5),
+            (3, 4, 3),
+            (5, 6, 7),
+        ] {
+            graph.add_edge(&TestGraphEdge::new(&parent, &child, format!("{}", weight)));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_minimum_spanning_forest() {
+        let graph = build_graph();
+        let weight = |edge: &TestGraphEdge| edge.label().parse::<i64>().unwrap();
+
+        let (forest, total) = graph.minimum_spanning_forest(weight);
+        assert_eq!(total, 1 + 2 + 3 + 7);
+        assert_eq!(forest.nodes().len(), 6);
+        assert_eq!(forest.edges().len(), 4);
+        assert_eq!(forest.weak_edges().len(), 0);
+        for (parent, child) in [(1, 3), (2, 3), (3, 4), (5, 6)] {
+            assert!(forest
+                .get_edge(&DirectedEdge::new(&parent, &child))
+                .is_some());
+        }
+
+        let (forest, total) = graph.maximum_spanning_forest(weight);
+        assert_eq!(total, 5 + 4 + 3 + 7);
+        for (parent, child) in [(1, 2), (2, 4), (3, 4), (5, 6)] {
+            assert!(forest
+                .get_edge(&DirectedEdge::new(&parent, &child))
+                .is_some());
+        }
+
+        let (_, total) =
+            graph.minimum_spanning_forest(|edge| edge.label().parse::<f64>().unwrap() / 2.0);
+        assert_eq!(total, 6.5);
+    }
+}
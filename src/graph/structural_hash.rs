@@ -0,0 +1,204 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+/// 64-bit FNV-1a. Unlike hashers of `HashMap`, results are the same across runs
+struct StructuralHasher(u64);
+
+impl StructuralHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StructuralHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes Merkle-style hashes of subtrees rooted at each node following edges (not weak edges).
+    /// A hash combines edge labels and node labels given by `node_label` but not node indexes,
+    /// so that subtrees of the same shape and labels have the same hash.
+    /// Returns GraphError::CycleDetected if edges make a cycle
+    pub fn subtree_hashes_by<L: Hash, F: Fn(&TEdge::Node) -> L>(
+        &self,
+        node_label: F,
+    ) -> Result<BTreeMap<&<TEdge::Node as Node>::NodeIndex, u64>, TEdge> {
+        let adjacency = Adjacency::new(self, false);
+        let hashes = self.__subtree_hashes(&adjacency, node_label)?;
+        Ok(adjacency.nodes.iter().copied().zip(hashes).collect())
+    }
+
+    /// Computes hashes of subtrees by their shapes and edge labels.
+    /// See DirectedGraph::subtree_hashes_by()
+    pub fn subtree_hashes(
+        &self,
+    ) -> Result<BTreeMap<&<TEdge::Node as Node>::NodeIndex, u64>, TEdge> {
+        self.subtree_hashes_by(|_| ())
+    }
+
+    /// Computes a hash of the whole graph from hashes of subtrees at roots (i.e. nodes without parents).
+    /// Graphs equal except for node indexes have the same hash. Intended for forests;
+    /// a DAG sharing a subtree may have the same hash as the tree duplicating it
+    pub fn structural_hash_by<L: Hash, F: Fn(&TEdge::Node) -> L>(
+        &self,
+        node_label: F,
+    ) -> Result<u64, TEdge> {
+        let adjacency = Adjacency::new(self, false);
+        let hashes = self.__subtree_hashes(&adjacency, node_label)?;
+        let mut roots: Vec<u64> = (0..adjacency.len())
+            .filter(|&v| adjacency.predecessors[v].is_empty())
+            .map(|v| hashes[v])
+            .collect();
+        roots.sort();
+
+        let mut hasher = StructuralHasher::new();
+        adjacency.len().hash(&mut hasher);
+        self.edges().len().hash(&mut hasher);
+        roots.hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    /// Computes a hash of the whole graph by its shape and edge labels.
+    /// See DirectedGraph::structural_hash_by()
+    pub fn structural_hash(&self) -> Result<u64, TEdge> {
+        self.structural_hash_by(|_| ())
+    }
+
+    fn __subtree_hashes<L: Hash, F: Fn(&TEdge::Node) -> L>(
+        &self,
+        adjacency: &Adjacency<'_, TEdge>,
+        node_label: F,
+    ) -> Result<Vec<u64>, TEdge> {
+        let order = adjacency.topological_order()?;
+        let mut hashes = vec![0; adjacency.len()];
+        for &v in order.iter().rev() {
+            // Children in `Ord` order of their hashes, so that the order of edges does not matter
+            let mut children: Vec<(u64, &str)> = adjacency.successors[v]
+                .iter()
+                .map(|&(child, edge)| (hashes[child], edge.label().as_str()))
+                .collect();
+            children.sort();
+
+            let mut hasher = StructuralHasher::new();
+            if let Some(node) = self.get_node(adjacency.nodes[v]) {
+                node_label(node).hash(&mut hasher);
+            }
+            children.hash(&mut hasher);
+            hashes[v] = hasher.finish();
+        }
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    #[cfg(not(feature = "metrics"))]
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+    use crate::node::Node;
+
+    use alloc::string::String;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize, &str)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child, label) in edges {
+            graph.add_edge(&TestGraphEdge::new(&parent, &child, String::from(label)));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_subtree_hashes() {
+        /*
+              (1)               (10)
+             a/ \b             b/  \a
+           (2)   (3)        (20)   (30)
+           c|   c/ \c      c/ \c   c|
+           (4) (5) (6)   (40) (60) (50)
+        */
+        let graph = build_graph(&[
+            (1, 2, "a"),
+            (1, 3, "b"),
+            (2, 4, "c"),
+            (3, 5, "c"),
+            (3, 6, "c"),
+        ]);
+        let other = build_graph(&[
+            (10, 20, "b"),
+            (10, 30, "a"),
+            (20, 40, "c"),
+            (30, 50, "c"),
+            (20, 60, "c"),
+        ]);
+
+        let hashes = graph.subtree_hashes().unwrap();
+        assert_eq!(hashes.len(), 6);
+        assert_eq!(hashes[&4], hashes[&5]);
+        assert_eq!(hashes[&5], hashes[&6]);
+        assert_ne!(hashes[&2], hashes[&3]);
+
+        let other_hashes = other.subtree_hashes().unwrap();
+        assert_eq!(hashes[&1], other_hashes[&10]);
+        assert_eq!(hashes[&3], other_hashes[&20]);
+        assert_eq!(graph.structural_hash(), other.structural_hash());
+
+        // Edge labels matter
+        let relabeled = build_graph(&[
+            (1, 2, "a"),
+            (1, 3, "b"),
+            (2, 4, "c"),
+            (3, 5, "c"),
+            (3, 6, "d"),
+        ]);
+        assert_ne!(graph.structural_hash(), relabeled.structural_hash());
+
+        // Node labels matter only if given
+        let hashes = graph.subtree_hashes_by(|node| *node.index() % 2).unwrap();
+        assert_ne!(hashes[&4], hashes[&5]);
+        assert_eq!(hashes[&4], hashes[&6]);
+    }
+
+    #[test]
+    fn test_directed_graph_structural_hash_forest() {
+        let graph = build_graph(&[(1, 2, "a"), (3, 4, "b")]);
+        let mut other = build_graph(&[(5, 6, "b"), (7, 8, "a")]);
+        assert_eq!(graph.structural_hash(), other.structural_hash());
+
+        other.add_node(&BasicNode::new(&9));
+        assert_ne!(graph.structural_hash(), other.structural_hash());
+    }
+
+    #[test]
+    fn test_directed_graph_structural_hash_cycle() {
+        // NOTE: With `metrics`, (3) -> (1) is a weak edge since it makes a cycle on the parent index
+        let graph = build_graph(&[(1, 2, "a"), (2, 3, "a"), (3, 1, "a")]);
+        #[cfg(feature = "metrics")]
+        assert!(graph.structural_hash().is_ok());
+        #[cfg(not(feature = "metrics"))]
+        assert!(matches!(
+            graph.structural_hash(),
+            Err(GraphError::CycleDetected(_))
+        ));
+    }
+}
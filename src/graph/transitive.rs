@@ -0,0 +1,206 @@
+use crate::edge::Edge;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+const BITS: usize = u64::BITS as usize;
+
+/// Reachability between all pairs of nodes stored in bitset (i.e. `n * n` bits).
+/// See DirectedGraph::transitive_closure()
+#[derive(Debug, Clone)]
+pub struct TransitiveClosure<'a, TIndex> {
+    nodes: Vec<&'a TIndex>,
+    id: HashMap<&'a TIndex, usize>,
+    /// Number of words in each row
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl<'a, TIndex: Eq + core::hash::Hash> TransitiveClosure<'a, TIndex> {
+    fn row(&self, from: usize) -> &[u64] {
+        &self.bits[from * self.words..(from + 1) * self.words]
+    }
+
+    fn get(&self, from: usize, to: usize) -> bool {
+        self.row(from)[to / BITS] & (1 << (to % BITS)) != 0
+    }
+
+    /// Returns true if there is a path `from -> ... -> to` of one or more edges.
+    /// NOTE: A node reaches itself only if it is on a cycle
+    pub fn reachable(&self, from: &TIndex, to: &TIndex) -> bool {
+        match (self.id.get(from), self.id.get(to)) {
+            (Some(&from), Some(&to)) => self.get(from, to),
+            _ => false,
+        }
+    }
+
+    /// Returns nodes reachable from `from` in `Ord` order
+    pub fn reachable_from(&self, from: &TIndex) -> Vec<&'a TIndex> {
+        match self.id.get(from) {
+            Some(&from) => (0..self.nodes.len())
+                .filter(|&to| self.get(from, to))
+                .map(|to| self.nodes[to])
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns number of reachable pairs
+    pub fn number_of_pairs(&self) -> usize {
+        self.bits.iter().map(|v| v.count_ones() as usize).sum()
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes reachability between all pairs of nodes following edges and weak edges
+    pub fn transitive_closure(&self) -> TransitiveClosure<'_, <TEdge::Node as Node>::NodeIndex> {
+        let adjacency = Adjacency::new(self, true);
+        let n = adjacency.len();
+        let words = n.div_ceil(BITS);
+        let mut bits = vec![0u64; n * words];
+
+        match adjacency.topological_order() {
+            Ok(order) => {
+                // DAG: merges rows of children in reverse topological order
+                for &v in order.iter().rev() {
+                    for &(child, _) in adjacency.successors[v].iter() {
+                        bits[v * words + child / BITS] |= 1 << (child % BITS);
+                        for i in 0..words {
+                            bits[v * words + i] |= bits[child * words + i];
+                        }
+                    }
+                }
+            }
+            Err(_) => {
+                // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+                for v in 0..n {
+                    let mut stack: Vec<usize> =
+                        adjacency.successors[v].iter().map(|v| v.0).collect();
+                    while let Some(u) = stack.pop() {
+                        let (word, bit) = (v * words + u / BITS, 1 << (u % BITS));
+                        if bits[word] & bit != 0 {
+                            continue;
+                        }
+                        bits[word] |= bit;
+                        stack.extend(adjacency.successors[u].iter().map(|v| v.0));
+                    }
+                }
+            }
+        }
+
+        TransitiveClosure {
+            nodes: adjacency.nodes,
+            id: adjacency.id,
+            words,
+            bits,
+        }
+    }
+
+    /// Returns a new graph without redundant edges (i.e. edges `a -> c` where `a -> b -> ... -> c` exists).
+    /// * Weak edges are taken into account, and remaining edges are added again with DirectedGraph::add_edge()
+    /// * Returns GraphError::CycleDetected if the graph is not a DAG since transitive reduction is not unique for cyclic graphs
+    pub fn transitive_reduction(&self) -> Result<DirectedGraph<TEdge>, TEdge> {
+        let adjacency = Adjacency::new(self, true);
+        adjacency.topological_order()?;
+        let closure = self.transitive_closure();
+
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            graph.add_node(node);
+        }
+        for successors in adjacency.successors.iter() {
+            for &(child, edge) in successors.iter() {
+                let redundant = successors
+                    .iter()
+                    .any(|&(other, _)| other != child && closure.get(other, child));
+                if !redundant {
+                    graph.add_edge(edge);
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child) in edges {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_transitive_closure() {
+        /*
+           (1)
+           / \
+         (2) (3)
+           \ /
+           (4) --> (5)
+        */
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5), (6, 6)]);
+        let closure = graph.transitive_closure();
+
+        assert!(closure.reachable(&1, &5));
+        assert!(closure.reachable(&3, &5));
+        assert!(!closure.reachable(&2, &3));
+        assert!(!closure.reachable(&1, &1));
+        assert!(closure.reachable(&6, &6));
+        assert_eq!(closure.reachable_from(&2), vec![&4, &5]);
+        assert_eq!(closure.number_of_pairs(), 4 + 2 + 2 + 1 + 1);
+
+        // Cyclic graph
+        let graph = build_graph(&[(1, 2), (2, 3), (3, 1), (3, 4)]);
+        let closure = graph.transitive_closure();
+        assert!(closure.reachable(&2, &1));
+        assert!(closure.reachable(&1, &1));
+        assert!(!closure.reachable(&4, &1));
+        assert_eq!(closure.reachable_from(&3), vec![&1, &2, &3, &4]);
+    }
+
+    #[test]
+    fn test_directed_graph_transitive_reduction() {
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 4), (3, 4), (1, 4), (4, 5), (2, 5)]);
+
+        let reduced = graph.transitive_reduction().unwrap();
+        assert_eq!(reduced.nodes().len(), 5);
+        assert_eq!(reduced.edges().len() + reduced.weak_edges().len(), 5);
+        assert!(reduced.get_edge(&DirectedEdge::new(&1, &4)).is_none());
+        assert!(reduced.get_weak_edge(&DirectedEdge::new(&1, &4)).is_none());
+        assert!(reduced.get_edge(&DirectedEdge::new(&2, &5)).is_none());
+        assert!(reduced.get_weak_edge(&DirectedEdge::new(&2, &5)).is_none());
+
+        let graph = build_graph(&[(1, 2), (2, 3), (3, 1)]);
+        assert!(matches!(
+            graph.transitive_reduction(),
+            Err(GraphError::CycleDetected(_))
+        ));
+        assert_eq!(graph.topological_sort(), Err(GraphError::CycleDetected(1)));
+        let graph = build_graph(&[(3, 1), (2, 1), (1, 4)]);
+        assert_eq!(graph.topological_sort(), Ok(vec![&2, &3, &1, &4]));
+    }
+}
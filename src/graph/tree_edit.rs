@@ -0,0 +1,399 @@
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::adjacency::Adjacency;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Add;
+
+/// Edit operation turning a forest into another. See DirectedGraph::tree_edit_script_by()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOperation<'a, TIndex> {
+    /// Deletes the node of the source forest, moving its children to its parent
+    Delete(&'a TIndex),
+    /// Inserts the node of the destination forest
+    Insert(&'a TIndex),
+    /// Maps the node of the source forest to the node of the destination forest, relabeling if needed
+    Match(&'a TIndex, &'a TIndex),
+}
+
+pub type EditScript<'a, TEdge> = Vec<EditOperation<'a, <<TEdge as Edge>::Node as Node>::NodeIndex>>;
+
+/// Nodes of a forest numbered in postorder, followed by a virtual root of all trees
+struct OrderedForest<'a, TEdge: Edge> {
+    nodes: Vec<&'a TEdge::Node>,
+    /// Leftmost leaf descendant of each node including the virtual root
+    leftmost: Vec<usize>,
+    /// Nodes having no ancestors of the same leftmost leaf, in ascending order
+    keyroots: Vec<usize>,
+}
+
+impl<'a, TEdge: Edge> OrderedForest<'a, TEdge> {
+    /// Orders trees and children in `Ord` order of their indexes, following edges (not weak edges)
+    fn new(graph: &'a DirectedGraph<TEdge>) -> Result<Self, TEdge> {
+        let adjacency = Adjacency::new(graph, false);
+        if let Some(v) = (0..adjacency.len()).find(|&v| adjacency.predecessors[v].len() > 1) {
+            return Err(GraphError::MultipleParents(adjacency.nodes[v].clone()));
+        }
+        adjacency.topological_order()?;
+
+        let n = adjacency.len();
+        let roots: Vec<usize> = (0..n)
+            .filter(|&v| adjacency.predecessors[v].is_empty())
+            .collect();
+        let children_of = |v: usize| -> Vec<usize> {
+            if v == n {
+                roots.clone()
+            } else {
+                adjacency.successors[v].iter().map(|v| v.0).collect()
+            }
+        };
+
+        // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+        let mut postorder: Vec<usize> = Vec::with_capacity(n + 1);
+        let mut stack = vec![(n, children_of(n), 0)];
+        while let Some((v, children, i)) = stack.last_mut() {
+            if let Some(&child) = children.get(*i) {
+                *i += 1;
+                let grandchildren = children_of(child);
+                stack.push((child, grandchildren, 0));
+                continue;
+            }
+            postorder.push(*v);
+            stack.pop();
+        }
+
+        // Leftmost leaves in postorder numbers: a node's leftmost leaf is that of its first child
+        let mut leftmost = vec![0; n + 1];
+        let mut number = vec![0; n + 1];
+        for (i, &v) in postorder.iter().enumerate() {
+            number[v] = i;
+        }
+        for (i, &v) in postorder.iter().enumerate() {
+            leftmost[i] = match children_of(v).first() {
+                Some(&child) => leftmost[number[child]],
+                None => i,
+            };
+        }
+
+        let mut highest = vec![None; n + 1];
+        for i in 0..=n {
+            highest[leftmost[i]] = Some(i);
+        }
+        let mut keyroots: Vec<usize> = highest.into_iter().flatten().collect();
+        keyroots.sort();
+
+        let nodes = postorder[..n]
+            .iter()
+            .filter_map(|&v| graph.get_node(adjacency.nodes[v]))
+            .collect();
+        Ok(Self {
+            nodes,
+            leftmost,
+            keyroots,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+fn minimum<W: PartialOrd>(a: W, b: W) -> W {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Tree distances and costs of Zhang-Shasha algorithm
+struct ZhangShasha<'a, 'b, TEdge: Edge, W> {
+    source: &'b OrderedForest<'a, TEdge>,
+    destination: &'b OrderedForest<'a, TEdge>,
+    delete: Vec<W>,
+    insert: Vec<W>,
+    relabel: Vec<Vec<W>>,
+    tree: Vec<Vec<W>>,
+}
+
+impl<'a, 'b, TEdge: Edge, W: PartialOrd + Copy + Add<Output = W> + Default>
+    ZhangShasha<'a, 'b, TEdge, W>
+{
+    /// Computes distances between forests of nodes from `leftmost(i)` to `i` and from `leftmost(j)` to `j`
+    fn forest_distance(&mut self, i: usize, j: usize) -> Vec<Vec<W>> {
+        let (li, lj) = (self.source.leftmost[i], self.destination.leftmost[j]);
+        let mut forest = vec![vec![W::default(); j - lj + 2]; i - li + 2];
+        for x in li..=i {
+            forest[x - li + 1][0] = forest[x - li][0] + self.delete[x];
+        }
+        for y in lj..=j {
+            forest[0][y - lj + 1] = forest[0][y - lj] + self.insert[y];
+        }
+        for x in li..=i {
+            for y in lj..=j {
+                let (dx, dy) = (x - li + 1, y - lj + 1);
+                let cost = minimum(
+                    forest[dx - 1][dy] + self.delete[x],
+                    forest[dx][dy - 1] + self.insert[y],
+                );
+                let (lx, ly) = (self.source.leftmost[x], self.destination.leftmost[y]);
+                forest[dx][dy] = if lx == li && ly == lj {
+                    let cost = minimum(cost, forest[dx - 1][dy - 1] + self.relabel[x][y]);
+                    self.tree[x][y] = cost;
+                    cost
+                } else {
+                    minimum(cost, forest[lx - li][ly - lj] + self.tree[x][y])
+                };
+            }
+        }
+        forest
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Computes tree edit distance between forests of this graph and `other` counting deleted and inserted nodes.
+    /// Labels are not compared. See DirectedGraph::tree_edit_distance_by()
+    pub fn tree_edit_distance(&self, other: &DirectedGraph<TEdge>) -> Result<usize, TEdge> {
+        self.tree_edit_distance_by(other, |_| 1, |_| 1, |_, _| 0)
+    }
+
+    /// Computes tree edit distance (Zhang-Shasha algorithm) between forests made of edges (not weak edges)
+    /// of this graph and `other`, with costs of deleting a node of this graph, inserting a node of `other`
+    /// and relabeling a node of this graph to a node of `other`.
+    /// * Trees and children are ordered by `Ord` of their indexes
+    /// * Returns GraphError::MultipleParents or GraphError::CycleDetected if edges do not make a forest
+    pub fn tree_edit_distance_by<W, D, I, R>(
+        &self,
+        other: &DirectedGraph<TEdge>,
+        delete: D,
+        insert: I,
+        relabel: R,
+    ) -> Result<W, TEdge>
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        D: Fn(&TEdge::Node) -> W,
+        I: Fn(&TEdge::Node) -> W,
+        R: Fn(&TEdge::Node, &TEdge::Node) -> W,
+    {
+        let (distance, _) = self.__tree_edit(other, delete, insert, relabel, false)?;
+        Ok(distance)
+    }
+
+    /// Computes tree edit distance and an edit script achieving it.
+    /// Deletions come first in postorder of this graph, followed by the other operations in postorder of `other`.
+    /// See DirectedGraph::tree_edit_distance_by()
+    pub fn tree_edit_script_by<'a, W, D, I, R>(
+        &'a self,
+        other: &'a DirectedGraph<TEdge>,
+        delete: D,
+        insert: I,
+        relabel: R,
+    ) -> Result<(W, EditScript<'a, TEdge>), TEdge>
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        D: Fn(&TEdge::Node) -> W,
+        I: Fn(&TEdge::Node) -> W,
+        R: Fn(&TEdge::Node, &TEdge::Node) -> W,
+    {
+        self.__tree_edit(other, delete, insert, relabel, true)
+    }
+
+    fn __tree_edit<'a, W, D, I, R>(
+        &'a self,
+        other: &'a DirectedGraph<TEdge>,
+        delete: D,
+        insert: I,
+        relabel: R,
+        script: bool,
+    ) -> Result<(W, EditScript<'a, TEdge>), TEdge>
+    where
+        W: PartialOrd + Copy + Add<Output = W> + Default,
+        D: Fn(&TEdge::Node) -> W,
+        I: Fn(&TEdge::Node) -> W,
+        R: Fn(&TEdge::Node, &TEdge::Node) -> W,
+    {
+        let source = OrderedForest::new(self)?;
+        let destination = OrderedForest::new(other)?;
+        let (n, m) = (source.len(), destination.len());
+
+        // Costs of virtual roots never count since they are excluded from the result
+        let zero = W::default();
+        let mut zs = ZhangShasha {
+            source: &source,
+            destination: &destination,
+            delete: source
+                .nodes
+                .iter()
+                .map(|&v| delete(v))
+                .chain([zero])
+                .collect(),
+            insert: destination
+                .nodes
+                .iter()
+                .map(|&v| insert(v))
+                .chain([zero])
+                .collect(),
+            relabel: (0..=n)
+                .map(|x| {
+                    (0..=m)
+                        .map(|y| match (source.nodes.get(x), destination.nodes.get(y)) {
+                            (Some(a), Some(b)) => relabel(a, b),
+                            _ => zero,
+                        })
+                        .collect()
+                })
+                .collect(),
+            tree: vec![vec![zero; m + 1]; n + 1],
+        };
+
+        let mut forest = Vec::new();
+        for &i in source.keyroots.iter() {
+            for &j in destination.keyroots.iter() {
+                forest = zs.forest_distance(i, j);
+            }
+        }
+        // The last keyroots are the virtual roots; the forests below them are the whole forests
+        let distance = forest[n][m];
+        if !script {
+            return Ok((distance, Vec::new()));
+        }
+
+        let mut operations: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+        let mut stack = vec![(n, m, n, m)];
+        while let Some((i, j, mut dx, mut dy)) = stack.pop() {
+            let forest = zs.forest_distance(i, j);
+            let (li, lj) = (source.leftmost[i], destination.leftmost[j]);
+            while dx > 0 || dy > 0 {
+                let (x, y) = ((li + dx).wrapping_sub(1), (lj + dy).wrapping_sub(1));
+                if dx > 0 && forest[dx][dy] == forest[dx - 1][dy] + zs.delete[x] {
+                    operations.push((Some(x), None));
+                    dx -= 1;
+                } else if dy > 0 && forest[dx][dy] == forest[dx][dy - 1] + zs.insert[y] {
+                    operations.push((None, Some(y)));
+                    dy -= 1;
+                } else if source.leftmost[x] == li && destination.leftmost[y] == lj {
+                    operations.push((Some(x), Some(y)));
+                    dx -= 1;
+                    dy -= 1;
+                } else {
+                    let (lx, ly) = (source.leftmost[x], destination.leftmost[y]);
+                    stack.push((x, y, x - lx + 1, y - ly + 1));
+                    dx = lx - li;
+                    dy = ly - lj;
+                }
+            }
+        }
+        operations.sort_by_key(|&(x, y)| (y, x));
+
+        let index = |forest: &OrderedForest<'a, TEdge>, v: usize| forest.nodes[v].index();
+        let operations = operations
+            .into_iter()
+            .filter_map(|operation| match operation {
+                (Some(x), Some(y)) => Some(EditOperation::Match(
+                    index(&source, x),
+                    index(&destination, y),
+                )),
+                (Some(x), None) => Some(EditOperation::Delete(index(&source, x))),
+                (None, Some(y)) => Some(EditOperation::Insert(index(&destination, y))),
+                (None, None) => None,
+            })
+            .collect();
+        Ok((distance, operations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::tree_edit::EditOperation;
+    use crate::node::basic_node::BasicNode;
+    use crate::node::Node;
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph(edges: &[(usize, usize)]) -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for &(parent, child) in edges {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_directed_graph_tree_edit_distance() {
+        /*
+               (1)              (1)
+              /   \              |
+            (2)   (3)           (4)
+             |                 /   \
+            (4)              (2)   (3)
+        */
+        let graph = build_graph(&[(1, 2), (1, 3), (2, 4)]);
+        let other = build_graph(&[(1, 4), (4, 2), (4, 3)]);
+
+        assert_eq!(graph.tree_edit_distance(&graph), Ok(0));
+        // Shapes are the same after deleting (2) and inserting a node above (2) and (3)
+        assert_eq!(graph.tree_edit_distance(&other), Ok(2));
+
+        // Relabeling by indexes
+        let relabel = |a: &BasicNode<usize>, b: &BasicNode<usize>| (a.index() != b.index()) as u32;
+        assert_eq!(
+            graph.tree_edit_distance_by(&other, |_| 1, |_| 1, relabel),
+            Ok(2)
+        );
+        let (distance, script) = graph
+            .tree_edit_script_by(&other, |_| 1, |_| 1, relabel)
+            .unwrap();
+        assert_eq!(distance, 2);
+        assert_eq!(
+            script,
+            [
+                EditOperation::Delete(&4),
+                EditOperation::Match(&2, &2),
+                EditOperation::Match(&3, &3),
+                EditOperation::Insert(&4),
+                EditOperation::Match(&1, &1),
+            ]
+        );
+
+        // Forests
+        let forest = build_graph(&[(1, 2), (3, 4), (3, 5)]);
+        let other = build_graph(&[(1, 2), (1, 6), (3, 4), (3, 5)]);
+        assert_eq!(forest.tree_edit_distance(&other), Ok(1));
+        let (_, script) = forest
+            .tree_edit_script_by(&other, |_| 1, |_| 1, |_, _| 0)
+            .unwrap();
+        let inserted: Vec<_> = script
+            .iter()
+            .filter_map(|v| match v {
+                EditOperation::Insert(v) => Some(**v),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(inserted, [6]);
+    }
+
+    #[test]
+    fn test_directed_graph_tree_edit_distance_not_forest() {
+        let graph = build_graph(&[(1, 2), (1, 3)]);
+        let dag = build_graph(&[(1, 3), (2, 3)]);
+        assert_eq!(
+            graph.tree_edit_distance(&dag),
+            Err(GraphError::MultipleParents(3))
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::graph::writer_option::TreeWriterOption;
+use crate::io;
+use crate::node::Node;
+use crate::result::Result;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use hashbrown::HashSet;
+
+struct Symbols {
+    branch: &'static str,
+    last_branch: &'static str,
+    vertical: &'static str,
+    space: &'static str,
+    elided: &'static str,
+    weak: &'static str,
+    truncated: &'static str,
+    repeated: &'static str,
+}
+
+const UNICODE_SYMBOLS: Symbols = Symbols {
+    branch: "├── ",
+    last_branch: "└── ",
+    vertical: "│   ",
+    space: "    ",
+    elided: "┄┄",
+    weak: "⤳",
+    truncated: "…",
+    repeated: "(*)",
+};
+
+const ASCII_SYMBOLS: Symbols = Symbols {
+    branch: "|-- ",
+    last_branch: "`-- ",
+    vertical: "|   ",
+    space: "    ",
+    elided: "--",
+    weak: "~>",
+    truncated: "...",
+    repeated: "(*)",
+};
+
+struct Frame<'a, TIndex> {
+    index: &'a TIndex,
+    prefix: String,
+    /// `None` for roots, `Some(is_last_sibling)` for others
+    is_last: Option<bool>,
+    depth: usize,
+}
+
+/// Adapts io::Write to fmt::Write keeping the original error
+struct IoWriter<'a, T: io::Write> {
+    inner: &'a mut T,
+    error: Option<io::Error>,
+}
+
+impl<'a, T: io::Write> fmt::Write for IoWriter<'a, T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge> {
+    /// Writes lineage forest like `tree` command. See DirectedGraph::tree_fmt()
+    pub fn tree_write<T: io::Write>(
+        &self,
+        file: &mut T,
+        option: &TreeWriterOption,
+    ) -> Result<(), TEdge> {
+        let mut writer = IoWriter {
+            inner: file,
+            error: None,
+        };
+        match self.tree_fmt(&mut writer, option) {
+            Ok(()) => Ok(()),
+            Err(_) => match writer.error {
+                Some(error) => Err(GraphError::from(error)),
+                None => Err(GraphError::IoError(String::from("formatter error"))),
+            },
+        }
+    }
+
+    /// Writes lineage forest like `tree` command.
+    /// * Walks from DirectedGraph::roots() along DirectedGraph::children_of()
+    /// * Roots and siblings are ordered by `Ord` of nodes
+    /// * Nodes appeared twice (i.e. nodes having multiple parents) are marked with `(*)` and not expanded again
+    pub fn tree_fmt<T: fmt::Write>(&self, f: &mut T, option: &TreeWriterOption) -> fmt::Result {
+        let symbols = if option.ascii {
+            &ASCII_SYMBOLS
+        } else {
+            &UNICODE_SYMBOLS
+        };
+
+        let mut weak_edges: BTreeMap<&<TEdge::Node as Node>::NodeIndex, Vec<&TEdge::Node>> =
+            BTreeMap::new();
+        if option.weak_edges {
+            for edge in self.weak_edges() {
+                if let Some(child) = self.get_node(edge.child()) {
+                    weak_edges
+                        .entry(edge.parent())
+                        .or_insert_with(Vec::new)
+                        .push(child);
+                }
+            }
+            for targets in weak_edges.values_mut() {
+                targets.sort();
+            }
+        }
+
+        let write_weak_edges =
+            |f: &mut T, index: &<TEdge::Node as Node>::NodeIndex| -> fmt::Result {
+                if let Some(targets) = weak_edges.get(index) {
+                    for target in targets {
+                        write!(f, " {} {}", symbols.weak, target)?;
+                    }
+                }
+                Ok(())
+            };
+        let write_node = |f: &mut T, index: &<TEdge::Node as Node>::NodeIndex| -> fmt::Result {
+            match self.get_node(index) {
+                Some(node) => write!(f, "{}", node)?,
+                None => write!(f, "{}", index)?,
+            }
+            write_weak_edges(f, index)
+        };
+
+        let mut printed = HashSet::with_capacity(self.nodes().len());
+        // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+        let mut stack = Vec::new();
+        for root in self
+            .sorted_nodes_of(self.roots().into_iter())
+            .into_iter()
+            .rev()
+        {
+            stack.push(Frame {
+                index: root,
+                prefix: String::new(),
+                is_last: None,
+                depth: 0,
+            });
+        }
+        while let Some(frame) = stack.pop() {
+            write!(f, "{}", frame.prefix)?;
+            match frame.is_last {
+                Some(true) => write!(f, "{}", symbols.last_branch)?,
+                Some(false) => write!(f, "{}", symbols.branch)?,
+                None => (),
+            }
+
+            write_node(f, frame.index)?;
+            if !printed.insert(frame.index) {
+                write!(f, " {}\n", symbols.repeated)?;
+                continue;
+            }
+
+            let (mut end, mut depth) = (frame.index, frame.depth);
+            if let Some(elide_chain) = option.elide_chain {
+                let mut chain = vec![frame.index];
+                while let Some(next) = self.__only_child_of(chain[chain.len() - 1]) {
+                    if printed.contains(next) || chain.contains(&next) {
+                        break;
+                    }
+                    // Do not elide nodes deeper than `max_depth`
+                    if option
+                        .max_depth
+                        .map_or(false, |v| frame.depth + chain.len() > v)
+                    {
+                        break;
+                    }
+                    chain.push(next);
+                }
+                if chain.len() >= 2 && chain.len() - 2 > elide_chain {
+                    end = chain[chain.len() - 1];
+                    depth = frame.depth + chain.len() - 1;
+                    write!(f, " {} ({} nodes elided", symbols.elided, chain.len() - 2)?;
+                    // Keep weak edges of elided nodes
+                    let mut separator = ";";
+                    for &index in chain[1..chain.len() - 1].iter() {
+                        if weak_edges.contains_key(index) {
+                            write!(f, "{} ", separator)?;
+                            write_node(f, index)?;
+                            separator = ",";
+                        }
+                    }
+                    write!(f, ") {} ", symbols.elided)?;
+                    write_node(f, end)?;
+                    printed.extend(chain.into_iter());
+                }
+            }
+
+            let children = match self.children_of(end) {
+                Some(children) => self.sorted_nodes_of(children.iter()),
+                None => Vec::new(),
+            };
+            if children.len() > 0 && option.max_depth.map_or(false, |v| depth >= v) {
+                write!(f, " {}\n", symbols.truncated)?;
+                continue;
+            }
+            write!(f, "\n")?;
+
+            let prefix = match frame.is_last {
+                Some(true) => format!("{}{}", frame.prefix, symbols.space),
+                Some(false) => format!("{}{}", frame.prefix, symbols.vertical),
+                None => frame.prefix,
+            };
+            let number_of_children = children.len();
+            for (i, child) in children.into_iter().enumerate().rev() {
+                stack.push(Frame {
+                    index: child,
+                    prefix: prefix.clone(),
+                    is_last: Some(i + 1 == number_of_children),
+                    depth: depth + 1,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn __only_child_of(
+        &self,
+        node: &<TEdge::Node as Node>::NodeIndex,
+    ) -> Option<&<TEdge::Node as Node>::NodeIndex> {
+        match self.children_of(node) {
+            Some(children) if children.len() == 1 => children.iter().next(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::graph::writer_option::TreeWriterOption;
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+
+    type TestGraphEdge = BasicEdge<BasicNode<usize>>;
+
+    fn build_graph() -> DirectedGraph<TestGraphEdge> {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+           (1)        (20)
+           / \
+         (3) (2)
+              |
+             (4)
+              |
+             ...
+              |
+             (9) ↺
+             / \
+          (10) (11) --> (1)
+        */
+        for (parent, child) in [
+            (1, 3),
+            (1, 2),
+            (2, 4),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (7, 8),
+            (8, 9),
+            (9, 10),
+            (9, 11),
+            (9, 9),
+            (11, 1),
+        ] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+        graph.add_node(&BasicNode::new(&20));
+        graph
+    }
+
+    #[test]
+    fn test_tree_fmt() {
+        let graph = build_graph();
+
+        let mut out = String::new();
+        assert!(graph.tree_fmt(&mut out, &TreeWriterOption::new()).is_ok());
+        assert_eq!(
+            out,
+            concat!(
+                "1\n",
+                "├── 2\n",
+                "│   └── 4\n",
+                "│       └── 5\n",
+                "│           └── 6\n",
+                "│               └── 7\n",
+                "│                   └── 8\n",
+                "│                       └── 9\n",
+                "│                           ├── 10\n",
+                "│                           └── 11\n",
+                "└── 3\n",
+                "20\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_tree_fmt_with_option() {
+        let graph = build_graph();
+
+        let mut out = String::new();
+        let option = TreeWriterOption::new()
+            .ascii(true)
+            .elide_chain(3)
+            .weak_edges(true);
+        assert!(graph.tree_fmt(&mut out, &option).is_ok());
+        assert_eq!(
+            out,
+            concat!(
+                "1\n",
+                "|-- 2 -- (5 nodes elided) -- 9 ~> 9\n",
+                "|   |-- 10\n",
+                "|   `-- 11 ~> 1\n",
+                "`-- 3\n",
+                "20\n",
+            )
+        );
+
+        let mut out = String::new();
+        let option = TreeWriterOption::new().max_depth(1);
+        assert!(graph.tree_fmt(&mut out, &option).is_ok());
+        assert_eq!(out, concat!("1\n", "├── 2 …\n", "└── 3\n", "20\n"));
+    }
+
+    #[test]
+    fn test_tree_fmt_elide_chain_with_option() {
+        let mut graph = build_graph();
+        graph.add_weak_edge(&TestGraphEdge::new(&5, &3, String::from("5->3")));
+        graph.add_weak_edge(&TestGraphEdge::new(&7, &20, String::from("7->20")));
+
+        // Weak edges of elided nodes are kept
+        let mut out = String::new();
+        let option = TreeWriterOption::new()
+            .ascii(true)
+            .elide_chain(3)
+            .weak_edges(true);
+        assert!(graph.tree_fmt(&mut out, &option).is_ok());
+        assert_eq!(
+            out,
+            concat!(
+                "1\n",
+                "|-- 2 -- (5 nodes elided; 5 ~> 3, 7 ~> 20) -- 9 ~> 9\n",
+                "|   |-- 10\n",
+                "|   `-- 11 ~> 1\n",
+                "`-- 3\n",
+                "20\n",
+            )
+        );
+
+        // Chain stops at `max_depth`: (2) is at depth 1 and (7) is at depth 5
+        let mut out = String::new();
+        let option = TreeWriterOption::new()
+            .ascii(true)
+            .elide_chain(2)
+            .max_depth(5);
+        assert!(graph.tree_fmt(&mut out, &option).is_ok());
+        assert_eq!(
+            out,
+            concat!(
+                "1\n",
+                "|-- 2 -- (3 nodes elided) -- 7 ...\n",
+                "`-- 3\n",
+                "20\n",
+            )
+        );
+
+        // Depth of the whole chain counts: (9) is at depth 7
+        let mut out = String::new();
+        let option = TreeWriterOption::new()
+            .ascii(true)
+            .elide_chain(3)
+            .max_depth(7);
+        assert!(graph.tree_fmt(&mut out, &option).is_ok());
+        assert_eq!(
+            out,
+            concat!(
+                "1\n",
+                "|-- 2 -- (5 nodes elided) -- 9 ...\n",
+                "`-- 3\n",
+                "20\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_tree_fmt_multiple_parents() {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        /*
+          (1) (2)
+            \ /
+            (3)
+             |
+            (4)
+        */
+        for (parent, child) in [(1, 3), (2, 3), (3, 4)] {
+            graph.add_edge(&TestGraphEdge::new(
+                &parent,
+                &child,
+                format!("{}->{}", parent, child),
+            ));
+        }
+
+        let mut out = String::new();
+        assert!(graph.tree_fmt(&mut out, &TreeWriterOption::new()).is_ok());
+        assert_eq!(
+            out,
+            concat!("1\n", "└── 3\n", "    └── 4\n", "2\n", "└── 3 (*)\n")
+        );
+    }
+}
@@ -1,21 +1,25 @@
 use crate::node::Node;
 
 use alloc::collections::BTreeMap;
-#[cfg(feature = "std")]
 use core::cmp::min;
 #[cfg(feature = "std")]
 #[allow(unused_imports)]
 use log::{info, trace};
 
+/// Disjoint sets united by size with path compression.
+/// The representative of a set is its smallest node regardless of the shape of the tree
 #[derive(Debug, Clone, Default)]
 pub struct UnionFindTree<TNode: Node> {
     parent: BTreeMap<TNode::NodeIndex, TNode::NodeIndex>, // Child --> Parent
+    /// Root --> (Number of nodes, Smallest node) of trees having multiple nodes
+    roots: BTreeMap<TNode::NodeIndex, (usize, TNode::NodeIndex)>,
 }
 
 impl<TNode: Node> UnionFindTree<TNode> {
     pub fn new() -> Self {
         Self {
             parent: BTreeMap::new(),
+            roots: BTreeMap::new(),
         }
     }
 
@@ -23,43 +27,70 @@ impl<TNode: Node> UnionFindTree<TNode> {
         self.parent.len()
     }
 
+    /// Returns the smallest node of the set containing `child`.
+    /// Trees are kept shallow by uniting by size, so that this takes O(log n) without compressing paths
     pub fn find(&self, child: &TNode::NodeIndex) -> TNode::NodeIndex {
         #[cfg(feature = "std")]
         trace!("find({:?})", child);
 
-        match self.parent.get(&child) {
-            Some(parent) => {
-                if parent == child {
-                    child.clone()
-                } else {
-                    self.find(parent)
-                }
-            }
-            None => child.clone(),
+        let root = self.__root_of(child);
+        match self.roots.get(root) {
+            Some((_, smallest)) => smallest.clone(),
+            None => root.clone(),
         }
     }
 
     pub fn unite(&mut self, x: &TNode::NodeIndex, y: &TNode::NodeIndex) -> () {
-        let root_x = self.find(&x);
-        let root_y = self.find(&y);
-
-        #[cfg(feature = "std")]
-        trace!("unite({:?}, {:?}) = {:?}", x, y, min(&root_x, &root_y));
-
+        let root_x = self.__compress(x);
+        let root_y = self.__compress(y);
         if root_x == root_y {
             return;
         }
 
-        if root_x < root_y {
-            // NOTE: Smaller node is parent
-            self.parent.insert(root_y, root_x);
+        let (size_x, smallest_x) = self.roots.remove(&root_x).unwrap_or((1, root_x.clone()));
+        let (size_y, smallest_y) = self.roots.remove(&root_y).unwrap_or((1, root_y.clone()));
+        let smallest = min(smallest_x, smallest_y);
+
+        #[cfg(feature = "std")]
+        trace!("unite({:?}, {:?}) = {:?}", x, y, smallest);
+
+        // NOTE: Smaller tree is child
+        let (root, child) = if size_x < size_y {
+            (root_y, root_x)
         } else {
-            self.parent.insert(root_x, root_y);
-        }
+            (root_x, root_y)
+        };
+        self.parent.insert(child, root.clone());
+        self.roots.insert(root, (size_x + size_y, smallest));
     }
 
     pub fn same(&self, x: &TNode::NodeIndex, y: &TNode::NodeIndex) -> bool {
-        self.find(x) == self.find(y)
+        self.__root_of(x) == self.__root_of(y)
+    }
+
+    fn __root_of<'a>(&'a self, child: &'a TNode::NodeIndex) -> &'a TNode::NodeIndex {
+        // NOTE: Do not recurse; lineages can be deep enough to overflow the stack
+        let mut current = child;
+        while let Some(parent) = self.parent.get(current) {
+            if parent == current {
+                break;
+            }
+            current = parent;
+        }
+        current
+    }
+
+    /// Returns the root of the tree containing `child` pointing nodes on the path at the root
+    fn __compress(&mut self, child: &TNode::NodeIndex) -> TNode::NodeIndex {
+        let root = self.__root_of(child).clone();
+        let mut current = child.clone();
+        while current != root {
+            match self.parent.insert(current, root.clone()) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        root
     }
 }
 
@@ -135,4 +166,37 @@ mod test {
         assert_eq!(T.find(&node_3.index()), 1);
         assert_eq!(T.find(&node_4.index()), 1);
     }
+
+    #[test]
+    fn test_union_find_tree_deep() {
+        #[allow(non_snake_case)]
+        let mut T = UnionFindTree::<BasicNode<usize>>::new();
+        // Merging trees of the same size pairwise makes them as deep as possible
+        let mut step = 1;
+        while step <= 100_000 {
+            for v in (0..=100_000 - step).step_by(step * 2) {
+                T.unite(&(v + step), &v);
+            }
+            step *= 2;
+        }
+        assert_eq!(T.find(&100_000), 0);
+        assert_eq!(T.number_of_nodes(), 100_000);
+
+        let depth_of = |tree: &UnionFindTree<BasicNode<usize>>, mut v: usize| {
+            let mut depth = 0;
+            while let Some(&parent) = tree.parent.get(&v) {
+                v = parent;
+                depth += 1;
+            }
+            depth
+        };
+        let deepest = (0..=100_000).max_by_key(|&v| depth_of(&T, v)).unwrap();
+        assert!(1 < depth_of(&T, deepest) && depth_of(&T, deepest) <= 17);
+
+        // Paths are compressed on uniting
+        T.unite(&deepest, &100_001);
+        assert_eq!(depth_of(&T, deepest), 1);
+        assert!(T.same(&0, &100_001));
+        assert_eq!(T.find(&100_001), 0);
+    }
 }
@@ -0,0 +1,141 @@
+use crate::graph::layout::LayoutOption;
+use core::fmt;
+
+/// Options for DirectedGraph::gml_write_with_option() and DirectedGraph::dot_write_with_option()
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct WriterOption {
+    /// Writes weak edges (i.e. self loops and edges making cycles) too.
+    /// Weak edges are flagged with `weak 1` in GML and drawn with dashed lines in DOT.
+    pub weak_edges: bool,
+    /// Annotates nodes with metrics (rank, is_root, subtree size and out-degree).
+    /// NOTE: Ignored without `metrics` feature since indexes are not available
+    pub metrics: bool,
+    /// (DOT only) Groups nodes into `subgraph cluster_*` blocks
+    pub cluster: Cluster,
+    /// (DOT only) Direction of graph layout
+    pub rankdir: Option<RankDir>,
+    /// (DOT only) Aligns nodes having the same rank (See DirectedGraph::rank_of()).
+    /// NOTE: Ignored without `metrics` feature since indexes are not available
+    pub rank_same: bool,
+    /// (GML only) Writes coordinates computed by DirectedGraph::layered_layout() into `graphics` sections
+    pub layout: Option<LayoutOption>,
+    /// Collapses chains having more than given number of nodes into summary nodes (See DirectedGraph::compress_chains())
+    pub compress_chains: Option<usize>,
+}
+
+impl WriterOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn weak_edges(mut self, enable: bool) -> Self {
+        self.weak_edges = enable;
+        self
+    }
+
+    pub fn metrics(mut self, enable: bool) -> Self {
+        self.metrics = enable;
+        self
+    }
+
+    pub fn cluster(mut self, cluster: Cluster) -> Self {
+        self.cluster = cluster;
+        self
+    }
+
+    pub fn rankdir(mut self, rankdir: RankDir) -> Self {
+        self.rankdir = Some(rankdir);
+        self
+    }
+
+    pub fn rank_same(mut self, enable: bool) -> Self {
+        self.rank_same = enable;
+        self
+    }
+
+    pub fn layout(mut self, layout: LayoutOption) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    pub fn compress_chains(mut self, min_length: usize) -> Self {
+        self.compress_chains = Some(min_length);
+        self
+    }
+}
+
+/// How to group nodes into clusters in DOT.
+/// To group by arbitrary key, use DirectedGraph::dot_write_clustered_by()
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Cluster {
+    #[default]
+    None,
+    /// One cluster per root node labeled with the root node
+    #[cfg(feature = "metrics")]
+    Root,
+    /// One cluster per weakly connected component labeled with its representative node
+    /// (See DirectedGraph::weakly_connected_components())
+    Component,
+    /// One cluster per community labeled with its id (See DirectedGraph::louvain())
+    Community,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RankDir {
+    TopToBottom,
+    LeftToRight,
+    BottomToTop,
+    RightToLeft,
+}
+
+impl fmt::Display for RankDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TopToBottom => write!(f, "TB"),
+            Self::LeftToRight => write!(f, "LR"),
+            Self::BottomToTop => write!(f, "BT"),
+            Self::RightToLeft => write!(f, "RL"),
+        }
+    }
+}
+
+/// Options for DirectedGraph::tree_write() and DirectedGraph::tree_fmt()
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TreeWriterOption {
+    /// Uses ASCII characters instead of Unicode box-drawing characters
+    pub ascii: bool,
+    /// Does not descend below given depth (Roots are depth 0)
+    pub max_depth: Option<usize>,
+    /// Collapses linear chains (i.e. nodes having only one child) into one line
+    /// if the chain has more than given number of intermediate nodes.
+    /// Elided nodes count for `max_depth`, and their weak edges are listed on the collapsed line
+    pub elide_chain: Option<usize>,
+    /// Marks weak edges (i.e. self loops and edges making cycles) pointing back into the tree
+    pub weak_edges: bool,
+}
+
+impl TreeWriterOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ascii(mut self, enable: bool) -> Self {
+        self.ascii = enable;
+        self
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    pub fn elide_chain(mut self, length: usize) -> Self {
+        self.elide_chain = Some(length);
+        self
+    }
+
+    pub fn weak_edges(mut self, enable: bool) -> Self {
+        self.weak_edges = enable;
+        self
+    }
+}
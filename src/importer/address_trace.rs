@@ -0,0 +1,276 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::edge::directed_edge::DirectedEdge;
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::io;
+use crate::node::basic_node::BasicNode;
+use crate::result::Result;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+pub type AddressTraceNode = BasicNode<u64>;
+/// Edge labeled with its hit count in decimal
+pub type AddressTraceEdge = BasicEdge<AddressTraceNode>;
+
+/// Number of records read at once
+const RECORDS_PER_READ: usize = 4096;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum AddressWidth {
+    U32,
+    #[default]
+    U64,
+}
+
+impl AddressWidth {
+    fn size(&self) -> usize {
+        match self {
+            Self::U32 => 4,
+            Self::U64 => 8,
+        }
+    }
+
+    fn read(&self, bytes: &[u8]) -> u64 {
+        let mut buffer = [0u8; 8];
+        buffer[..self.size()].copy_from_slice(&bytes[..self.size()]);
+        u64::from_le_bytes(buffer)
+    }
+}
+
+/// Options for DirectedGraph::address_trace_read() and DirectedGraph::address_trace_add().
+/// A record is little-endian `source`, `target` and optional `count` with the same width
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AddressTraceOption {
+    pub width: AddressWidth,
+    /// Each record has a count field. Otherwise each record counts as one hit
+    pub count: bool,
+}
+
+impl AddressTraceOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn width(mut self, width: AddressWidth) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn count(mut self, enable: bool) -> Self {
+        self.count = enable;
+        self
+    }
+
+    fn record_size(&self) -> usize {
+        self.width.size() * if self.count { 3 } else { 2 }
+    }
+}
+
+impl DirectedGraph<AddressTraceEdge> {
+    /// Builds a call/branch graph from a trace of binary address pairs. See DirectedGraph::address_trace_add()
+    pub fn address_trace_read<R: io::Read>(
+        name: String,
+        file: &mut R,
+        option: &AddressTraceOption,
+    ) -> Result<Self, AddressTraceEdge> {
+        let mut graph = Self::new(name);
+        graph.address_trace_add(file, option)?;
+        Ok(graph)
+    }
+
+    /// Reads a trace of binary address pairs until EOF and adds hit counts to edges.
+    /// * Existing hit counts (See DirectedGraph::hit_count_of()) are accumulated, so that traces can be fed one by one
+    /// * Hit counts saturate at `u64::MAX` since count fields of corrupt traces can be anything
+    /// * New edges are added in order of their first appearance in the trace
+    /// * Returns the number of records read
+    pub fn address_trace_add<R: io::Read>(
+        &mut self,
+        file: &mut R,
+        option: &AddressTraceOption,
+    ) -> Result<usize, AddressTraceEdge> {
+        let record_size = option.record_size();
+        let width = option.width.size();
+
+        // NOTE: Aggregate in memory first since traces typically repeat a small set of edges many times
+        let mut position: HashMap<(u64, u64), usize> = HashMap::with_capacity(8);
+        let mut hits: Vec<((u64, u64), u64)> = Vec::new();
+        let mut number_of_records = 0;
+
+        let mut buffer = vec![0u8; record_size * RECORDS_PER_READ];
+        let mut filled = 0;
+        loop {
+            let length = match file.read(&mut buffer[filled..]) {
+                Ok(length) => length,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(GraphError::from(error)),
+            };
+            if length == 0 {
+                break;
+            }
+            filled += length;
+
+            let complete = filled - filled % record_size;
+            for record in buffer[..complete].chunks_exact(record_size) {
+                let source = option.width.read(&record[0..]);
+                let target = option.width.read(&record[width..]);
+                let count = if option.count {
+                    option.width.read(&record[width * 2..])
+                } else {
+                    1
+                };
+                match position.get(&(source, target)) {
+                    Some(&i) => hits[i].1 = hits[i].1.saturating_add(count),
+                    None => {
+                        position.insert((source, target), hits.len());
+                        hits.push(((source, target), count));
+                    }
+                }
+                number_of_records += 1;
+            }
+            buffer.copy_within(complete..filled, 0);
+            filled -= complete;
+        }
+        if filled > 0 {
+            return Err(GraphError::ParseError(format!(
+                "truncated record at offset {:#x}",
+                number_of_records * record_size
+            )));
+        }
+
+        for ((source, target), count) in hits {
+            let count = self
+                .hit_count_of(&source, &target)
+                .unwrap_or(0)
+                .saturating_add(count);
+            let edge = AddressTraceEdge::new(&source, &target, format!("{}", count));
+            if !self.replace_edge(&edge) {
+                self.add_edge(&edge);
+            }
+        }
+        Ok(number_of_records)
+    }
+
+    /// Returns hit count of edge (or weak edge) `source -> target`.
+    /// Edges whose label is not a number count as zero
+    pub fn hit_count_of(&self, source: &u64, target: &u64) -> Option<u64> {
+        let arrow = DirectedEdge::new(source, target);
+        self.get_edge(&arrow)
+            .or_else(|| self.get_weak_edge(&arrow))
+            .map(|edge| edge.label().parse().unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::importer::address_trace::{AddressTraceOption, AddressWidth};
+
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use std::io;
+
+    /// Returns bytes one by one to exercise records split across reads
+    struct SlowReader<'a>(&'a [u8]);
+
+    impl<'a> io::Read for SlowReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.len() == 0 || buf.len() == 0 {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_address_trace_read() {
+        let mut trace = Vec::new();
+        for (source, target) in [
+            (0x421493u64, 0x41c2d9u64),
+            (0x41c2d9, 0x402566),
+            (0x421493, 0x41c2d9),
+            (0x41c33c, 0x421493),
+        ] {
+            trace.extend_from_slice(&source.to_le_bytes());
+            trace.extend_from_slice(&target.to_le_bytes());
+        }
+
+        let mut graph = DirectedGraph::address_trace_read(
+            String::from("test"),
+            &mut SlowReader(&trace),
+            &AddressTraceOption::new(),
+        )
+        .unwrap();
+        assert_eq!(graph.nodes().len(), 4);
+        assert_eq!(graph.edges().len(), 3);
+        assert_eq!(graph.hit_count_of(&0x421493, &0x41c2d9), Some(2));
+        assert_eq!(graph.hit_count_of(&0x41c2d9, &0x402566), Some(1));
+        assert_eq!(graph.hit_count_of(&0x402566, &0x41c2d9), None);
+
+        // Feeding the same trace again accumulates counts
+        assert_eq!(
+            graph.address_trace_add(&mut &trace[..], &AddressTraceOption::new()),
+            Ok(4)
+        );
+        assert_eq!(graph.edges().len(), 3);
+        assert_eq!(graph.hit_count_of(&0x421493, &0x41c2d9), Some(4));
+    }
+
+    #[test]
+    fn test_address_trace_read_u32_with_count() {
+        let mut trace = Vec::new();
+        for (source, target, count) in [(0x1000u32, 0x2000u32, 5u32), (0x2000, 0x2000, 7)] {
+            trace.extend_from_slice(&source.to_le_bytes());
+            trace.extend_from_slice(&target.to_le_bytes());
+            trace.extend_from_slice(&count.to_le_bytes());
+        }
+        let option = AddressTraceOption::new()
+            .width(AddressWidth::U32)
+            .count(true);
+
+        let graph =
+            DirectedGraph::address_trace_read(String::from("test"), &mut &trace[..], &option)
+                .unwrap();
+        assert_eq!(graph.hit_count_of(&0x1000, &0x2000), Some(5));
+        // Self loop is kept as a weak edge
+        assert_eq!(graph.hit_count_of(&0x2000, &0x2000), Some(7));
+
+        assert!(matches!(
+            DirectedGraph::address_trace_read(
+                String::from("test"),
+                &mut &trace[..trace.len() - 1],
+                &option
+            ),
+            Err(GraphError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_address_trace_read_count_saturates() {
+        let mut trace = Vec::new();
+        for count in [u64::MAX - 1, 2, u64::MAX] {
+            trace.extend_from_slice(&0x1000u64.to_le_bytes());
+            trace.extend_from_slice(&0x2000u64.to_le_bytes());
+            trace.extend_from_slice(&count.to_le_bytes());
+        }
+        let option = AddressTraceOption::new().count(true);
+
+        let mut graph =
+            DirectedGraph::address_trace_read(String::from("test"), &mut &trace[..], &option)
+                .unwrap();
+        assert_eq!(graph.hit_count_of(&0x1000, &0x2000), Some(u64::MAX));
+
+        // Accumulating onto existing counts saturates too
+        assert_eq!(graph.address_trace_add(&mut &trace[..48], &option), Ok(2));
+        assert_eq!(graph.hit_count_of(&0x1000, &0x2000), Some(u64::MAX));
+    }
+}
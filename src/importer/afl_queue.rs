@@ -0,0 +1,392 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::metadata_node::MetadataNode;
+use crate::node::node_index::NodeIndex;
+use crate::result::Result;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+pub type AflQueueNode = MetadataNode<AflQueueId, AflQueueEntry>;
+pub type AflQueueEdge = BasicEdge<AflQueueNode>;
+
+/// Identifies a queue entry among fuzzer instances (i.e. `-M`/`-S` workers sharing a sync directory).
+/// `worker` is empty if the queue is imported as a single fuzzer instance.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default, Hash)]
+pub struct AflQueueId {
+    pub worker: String,
+    pub id: u32,
+}
+
+impl NodeIndex for AflQueueId {}
+
+impl AflQueueId {
+    pub fn new(worker: &str, id: u32) -> Self {
+        Self {
+            worker: String::from(worker),
+            id,
+        }
+    }
+}
+
+impl fmt::Display for AflQueueId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.worker.len() > 0 {
+            write!(f, "{}:{:06}", self.worker, self.id)
+        } else {
+            write!(f, "{:06}", self.id)
+        }
+    }
+}
+
+/// Fields of AFL/AFL++ queue file name like `id:000123,src:000045+000067,time:1234,execs:5678,op:splice,rep:4,+cov`
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default, Hash)]
+pub struct AflQueueEntry {
+    pub id: u32,
+    /// Parent entries. Splices have two sources
+    pub sources: Vec<u32>,
+    /// Name of the worker this entry was synced from. `sources` are ids in that worker's queue
+    pub sync: Option<String>,
+    /// Name of the original seed file
+    pub orig: Option<String>,
+    pub op: Option<String>,
+    pub rep: Option<u32>,
+    /// Milliseconds since the fuzzer started (AFL++ only)
+    pub time: Option<u64>,
+    /// Number of executions when this entry was found (AFL++ only)
+    pub execs: Option<u64>,
+    /// Fields not listed above as they are (e.g. `pos:12`, `val:+3`, `sig:11` and `+cov`)
+    pub extra: Vec<String>,
+}
+
+impl fmt::Display for AflQueueEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = Vec::new();
+        if let Some(orig) = &self.orig {
+            fields.push(format!("orig:{}", orig));
+        }
+        if let Some(sync) = &self.sync {
+            fields.push(format!("sync:{}", sync));
+        }
+        if let Some(op) = &self.op {
+            fields.push(format!("op:{}", op));
+        }
+        if let Some(rep) = &self.rep {
+            fields.push(format!("rep:{}", rep));
+        }
+        write!(f, "{}", fields.join(","))
+    }
+}
+
+impl FromStr for AflQueueEntry {
+    type Err = GraphError<AflQueueNode>;
+
+    /// Parses queue file name. Fields other than `id` are optional
+    fn from_str(file_name: &str) -> Result<Self, AflQueueEdge> {
+        let error = |why: &str| {
+            GraphError::ParseError(format!("{} in AFL queue entry {:?}", why, file_name))
+        };
+        let number = |value: &str| -> Result<u64, AflQueueEdge> {
+            value.parse().map_err(|_| error("invalid number"))
+        };
+        // NOTE: Out-of-range ids must be errors; truncating them would merge different entries
+        let id = |value: &str| -> Result<u32, AflQueueEdge> {
+            value.parse().map_err(|_| error("invalid id"))
+        };
+
+        let mut entry = Self::default();
+        let mut has_id = false;
+        let mut rest = file_name;
+        while rest.len() > 0 {
+            // NOTE: Original seed names may contain commas, so `orig:` takes the rest of file name
+            if let Some(orig) = rest.strip_prefix("orig:") {
+                entry.orig = Some(String::from(orig));
+                break;
+            }
+            let (field, next) = match rest.split_once(',') {
+                Some((field, next)) => (field, next),
+                None => (rest, ""),
+            };
+            rest = next;
+
+            match field.split_once(':') {
+                Some(("id", value)) if !has_id => {
+                    entry.id = id(value)?;
+                    has_id = true;
+                }
+                Some(("src", value)) => {
+                    for source in value.split('+') {
+                        entry.sources.push(id(source)?);
+                    }
+                }
+                Some(("sync", value)) => entry.sync = Some(String::from(value)),
+                Some(("op", value)) => entry.op = Some(String::from(value)),
+                Some(("rep", value)) => entry.rep = Some(id(value)?),
+                Some(("time", value)) => entry.time = Some(number(value)?),
+                Some(("execs", value)) => entry.execs = Some(number(value)?),
+                _ => entry.extra.push(String::from(field)),
+            }
+        }
+        if !has_id {
+            return Err(error("missing id"));
+        }
+        Ok(entry)
+    }
+}
+
+impl DirectedGraph<AflQueueEdge> {
+    /// Adds a queue entry of `worker` and edges from its sources.
+    /// * Edges are labeled with `op` (`sync:NAME` for synced entries)
+    /// * Sources of synced entries are looked up in the queue of the worker named by `sync`
+    /// * Sources not imported yet are added with empty metadata and overwritten when imported later
+    pub fn afl_queue_add(&mut self, worker: &str, file_name: &str) -> Result<(), AflQueueEdge> {
+        let entry: AflQueueEntry = file_name.parse()?;
+        let index = AflQueueId::new(worker, entry.id);
+        let (source_worker, label) = match &entry.sync {
+            Some(sync) => (sync.as_str(), format!("sync:{}", sync)),
+            None => (worker, entry.op.clone().unwrap_or_default()),
+        };
+
+        self.add_node(&AflQueueNode::new(&index, &entry));
+        for source in entry.sources.iter() {
+            self.add_edge(&AflQueueEdge::new(
+                &AflQueueId::new(source_worker, *source),
+                &index,
+                label.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads file names in AFL/AFL++ queue directory (e.g. `out/default/queue`) into a graph.
+    /// Files not starting with `id:` (e.g. `.state`) are ignored
+    #[cfg(feature = "std")]
+    pub fn afl_queue_read_dir<P: AsRef<Path>>(
+        name: String,
+        worker: &str,
+        path: P,
+    ) -> Result<Self, AflQueueEdge> {
+        let mut graph = Self::new(name);
+        graph.afl_queue_add_dir(worker, path)?;
+        Ok(graph)
+    }
+
+    /// Reads all workers' queue directories (i.e. `SYNC_DIR/*/queue`) into a graph
+    /// so that synced entries are connected to their sources in other workers
+    #[cfg(feature = "std")]
+    pub fn afl_sync_read_dir<P: AsRef<Path>>(name: String, path: P) -> Result<Self, AflQueueEdge> {
+        let mut workers = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let queue = entry.path().join("queue");
+            if queue.is_dir() {
+                workers.push((entry.file_name().to_string_lossy().into_owned(), queue));
+            }
+        }
+        // NOTE: Sort to make the result independent from order of directory entries
+        workers.sort();
+
+        let mut graph = Self::new(name);
+        for (worker, queue) in workers {
+            graph.afl_queue_add_dir(&worker, queue)?;
+        }
+        Ok(graph)
+    }
+
+    #[cfg(feature = "std")]
+    fn afl_queue_add_dir<P: AsRef<Path>>(
+        &mut self,
+        worker: &str,
+        path: P,
+    ) -> Result<(), AflQueueEdge> {
+        let mut file_names = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let file_name = entry?.file_name().to_string_lossy().into_owned();
+            if file_name.starts_with("id:") {
+                file_names.push(file_name);
+            }
+        }
+        // NOTE: Add sources before derived entries so that indexes follow the first source
+        file_names.sort();
+
+        for file_name in file_names {
+            self.afl_queue_add(worker, &file_name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::error::GraphError;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::importer::afl_queue::{AflQueueEdge, AflQueueEntry, AflQueueId};
+
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+
+    #[test]
+    fn test_afl_queue_entry_parse() {
+        let entry: AflQueueEntry =
+            "id:000123,src:000045+000067,time:1234,execs:5678,op:splice,rep:4,+cov"
+                .parse()
+                .unwrap();
+        assert_eq!(entry.id, 123);
+        assert_eq!(entry.sources, vec![45, 67]);
+        assert_eq!(entry.op, Some(String::from("splice")));
+        assert_eq!(entry.rep, Some(4));
+        assert_eq!(entry.time, Some(1234));
+        assert_eq!(entry.execs, Some(5678));
+        assert_eq!(entry.extra, vec![String::from("+cov")]);
+        assert_eq!(format!("{}", entry), "op:splice,rep:4");
+
+        let entry: AflQueueEntry = "id:000000,time:0,execs:0,orig:seed,with,commas"
+            .parse()
+            .unwrap();
+        assert_eq!(entry.orig, Some(String::from("seed,with,commas")));
+        assert_eq!(entry.sources, vec![]);
+
+        let entry: AflQueueEntry = "id:000009,sync:fuzzer02,src:000012".parse().unwrap();
+        assert_eq!(entry.sync, Some(String::from("fuzzer02")));
+        assert_eq!(entry.sources, vec![12]);
+
+        assert!(matches!(
+            "src:000001,op:havoc".parse::<AflQueueEntry>(),
+            Err(GraphError::ParseError(_))
+        ));
+        assert!(matches!(
+            "id:000001,src:abc".parse::<AflQueueEntry>(),
+            Err(GraphError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_afl_queue_entry_parse_out_of_range() {
+        let entry: AflQueueEntry = "id:4294967295,src:4294967295".parse().unwrap();
+        assert_eq!(entry.id, u32::MAX);
+
+        // Must not be truncated into `id:0`
+        for file_name in [
+            "id:4294967296",
+            "id:000001,src:000000+4294967296",
+            "id:000001,rep:4294967296",
+        ] {
+            assert!(
+                matches!(
+                    file_name.parse::<AflQueueEntry>(),
+                    Err(GraphError::ParseError(_))
+                ),
+                "{}",
+                file_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_afl_queue_add() {
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (worker, file_name) in [
+            ("main", "id:000000,time:0,execs:0,orig:seed"),
+            (
+                "main",
+                "id:000001,src:000000,time:10,execs:20,op:havoc,rep:4,+cov",
+            ),
+            (
+                "main",
+                "id:000002,src:000000+000001,time:30,execs:40,op:splice,rep:8",
+            ),
+            ("sub", "id:000000,time:0,execs:0,orig:seed"),
+            ("sub", "id:000001,sync:main,src:000002"),
+        ] {
+            assert_eq!(graph.afl_queue_add(worker, file_name), Ok(()));
+        }
+
+        assert_eq!(graph.nodes().len(), 5);
+        let node = graph.get_node(&AflQueueId::new("main", 1)).unwrap();
+        assert_eq!(node.metadata().execs, Some(20));
+        assert_eq!(format!("{}", node), "op:havoc,rep:4 (main:000001)");
+
+        let edge = |parent: (&str, u32), child: (&str, u32)| {
+            AflQueueEdge::new(
+                &AflQueueId::new(parent.0, parent.1),
+                &AflQueueId::new(child.0, child.1),
+                String::new(),
+            )
+        };
+        let label_of = |edge: &AflQueueEdge| {
+            graph
+                .get_edge(&DirectedEdge::from(edge))
+                .or_else(|| {
+                    graph
+                        .weak_edges()
+                        .find(|v| v.parent() == edge.parent() && v.child() == edge.child())
+                })
+                .map(|v| v.label().clone())
+        };
+        assert_eq!(
+            label_of(&edge(("main", 0), ("main", 1))),
+            Some(String::from("havoc"))
+        );
+        // Splice has two sources
+        assert_eq!(
+            label_of(&edge(("main", 0), ("main", 2))),
+            Some(String::from("splice"))
+        );
+        assert_eq!(
+            label_of(&edge(("main", 1), ("main", 2))),
+            Some(String::from("splice"))
+        );
+        // Synced entry refers to the other worker's queue
+        assert_eq!(
+            label_of(&edge(("main", 2), ("sub", 1))),
+            Some(String::from("sync:main"))
+        );
+        assert_eq!(
+            graph
+                .get_node(&AflQueueId::new("sub", 0))
+                .and_then(|v| v.metadata().orig.clone()),
+            Some(String::from("seed"))
+        );
+    }
+
+    #[test]
+    fn test_afl_sync_read_dir() {
+        let root = std::env::temp_dir().join(format!("teapot-afl-sync-{}", std::process::id()));
+        for (worker, file_name) in [
+            ("main", "id:000000,time:0,execs:0,orig:seed"),
+            (
+                "main",
+                "id:000001,src:000000,time:10,execs:20,op:havoc,rep:4",
+            ),
+            ("main", ".state"),
+            ("sub", "id:000000,sync:main,src:000001"),
+        ] {
+            let queue = root.join(worker).join("queue");
+            std::fs::create_dir_all(&queue).unwrap();
+            std::fs::write(queue.join(file_name), b"").unwrap();
+        }
+
+        let graph = DirectedGraph::afl_sync_read_dir(String::from("test"), &root);
+        std::fs::remove_dir_all(&root).unwrap();
+        let graph = graph.unwrap();
+
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(graph.edges().len(), 2);
+        assert!(graph
+            .edges()
+            .any(|v| v.parent() == &AflQueueId::new("main", 1)
+                && v.child() == &AflQueueId::new("sub", 0)));
+    }
+}
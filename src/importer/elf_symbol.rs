@@ -0,0 +1,334 @@
+use crate::edge::basic_edge::BasicEdge;
+use crate::edge::Edge;
+use crate::error::GraphError;
+use crate::graph::directed_graph::DirectedGraph;
+use crate::node::metadata_node::MetadataNode;
+use crate::node::node_index::NodeIndex;
+use crate::node::Node;
+use crate::result::Result;
+
+use addr2line::gimli::{EndianRcSlice, RunTimeEndian};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use object::{Object, ObjectSymbol, SymbolKind};
+use std::path::Path;
+
+/// Code address written in hex
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Default, Hash)]
+pub struct Address(pub u64);
+
+impl NodeIndex for Address {}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+/// Address node labeled with symbol (e.g. `func+0x1a (file.c:42)`)
+pub type SymbolNode = MetadataNode<Address, String>;
+pub type SymbolEdge = BasicEdge<SymbolNode>;
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct Symbol {
+    address: u64,
+    size: u64,
+    name: String,
+}
+
+/// Resolves code addresses to function symbols (and optionally to source lines) of an ELF file.
+/// NOTE: Addresses are file addresses; subtract load base from runtime addresses of PIE/shared objects before lookup
+pub struct ElfSymbolizer {
+    /// Function symbols sorted by address
+    symbols: Vec<Symbol>,
+    lines: Option<addr2line::Context<EndianRcSlice<RunTimeEndian>>>,
+}
+
+impl ElfSymbolizer {
+    /// Reads symbol tables (`.symtab` and `.dynsym`) of ELF file.
+    /// Also reads DWARF line info if `line_info` is true and the file has it
+    pub fn open<P: AsRef<Path>>(path: P, line_info: bool) -> Result<Self, SymbolEdge> {
+        let data = std::fs::read(path)?;
+        let file = match object::File::parse(&*data) {
+            Ok(file) => file,
+            Err(why) => return Err(GraphError::ParseError(format!("{}", why))),
+        };
+
+        let mut symbols: Vec<Symbol> = file
+            .symbols()
+            .chain(file.dynamic_symbols())
+            .filter(|v| v.kind() == SymbolKind::Text && v.is_definition() && v.address() != 0)
+            .filter_map(|v| {
+                Some(Symbol {
+                    address: v.address(),
+                    size: v.size(),
+                    name: String::from(v.name().ok()?),
+                })
+            })
+            .collect();
+        symbols.sort();
+        // NOTE: Aliases share the same address. Keep the first one in `Ord` order to be deterministic
+        symbols.dedup_by_key(|v| v.address);
+
+        let lines = if line_info && file.has_debug_symbols() {
+            match addr2line::Context::new(&file) {
+                Ok(context) => Some(context),
+                Err(why) => return Err(GraphError::ParseError(format!("{}", why))),
+            }
+        } else {
+            None
+        };
+
+        Ok(Self { symbols, lines })
+    }
+
+    /// Returns address of function named `name`
+    pub fn address_of(&self, name: &str) -> Option<u64> {
+        self.symbols
+            .iter()
+            .find(|v| v.name == name)
+            .map(|v| v.address)
+    }
+
+    /// Returns start address and name of the function containing `address`
+    pub fn function_of(&self, address: u64) -> Option<(u64, &str)> {
+        let i = self.symbols.partition_point(|v| v.address <= address);
+        if i == 0 {
+            return None;
+        }
+        let symbol = &self.symbols[i - 1];
+        // NOTE: Some symbols (e.g. hand-written assembly) have no size. Assume they extend to the next symbol
+        if symbol.size == 0 || address < symbol.address + symbol.size {
+            Some((symbol.address, symbol.name.as_str()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns source file name (without directories) and line of `address`
+    pub fn line_of(&self, address: u64) -> Option<(&str, u32)> {
+        let location = self.lines.as_ref()?.find_location(address).ok()??;
+        let file = location.file?;
+        let file = file.rsplit(['/', '\\']).next().unwrap_or(file);
+        Some((file, location.line?))
+    }
+
+    /// Returns label like `func+0x1a (file.c:42)`. Unknown parts are omitted, falling back to hex address
+    pub fn label_of(&self, address: u64) -> String {
+        let mut label = match self.function_of(address) {
+            Some((start, name)) if start == address => String::from(name),
+            Some((start, name)) => format!("{}+{:#x}", name, address - start),
+            None => format!("{:#x}", address),
+        };
+        if let Some((file, line)) = self.line_of(address) {
+            label.push_str(&format!(" ({}:{})", file, line));
+        }
+        label
+    }
+}
+
+impl<TEdge: Edge> DirectedGraph<TEdge>
+where
+    TEdge::Node: Node<NodeIndex = u64>,
+{
+    /// Returns the same graph whose nodes are labeled with ElfSymbolizer::label_of()
+    pub fn symbolize(&self, symbolizer: &ElfSymbolizer) -> DirectedGraph<SymbolEdge> {
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in self.nodes() {
+            graph.add_node(&SymbolNode::new(
+                &Address(*node.index()),
+                &symbolizer.label_of(*node.index()),
+            ));
+        }
+        for edge in self.edges() {
+            graph.add_edge(&SymbolEdge::new(
+                &Address(*edge.parent()),
+                &Address(*edge.child()),
+                edge.label().clone(),
+            ));
+        }
+        for edge in self.weak_edges() {
+            graph.add_weak_edge(&SymbolEdge::new(
+                &Address(*edge.parent()),
+                &Address(*edge.child()),
+                edge.label().clone(),
+            ));
+        }
+        graph
+    }
+
+    /// Collapses basic-block addresses into per-function nodes labeled with function names. See DirectedGraph::quotient()
+    /// * Addresses not covered by any function remain as they are
+    /// * Edges (including weak edges) between the same pair of functions are merged into one edge.
+    ///   Calls inside a function become self loops
+    /// * Labels of merged edges are summed up (saturating at `u64::MAX`) if all of them are numbers
+    ///   (e.g. hit counts of DirectedGraph::address_trace_add()).
+    ///   Otherwise the label of the first edge in `Ord` order is kept
+    pub fn coarsen_by_function(&self, symbolizer: &ElfSymbolizer) -> DirectedGraph<SymbolEdge> {
+        let (quotient, _) = self.quotient(
+            |node| match symbolizer.function_of(*node.index()) {
+                Some((start, _)) => Address(start),
+                None => Address(*node.index()),
+            },
+            |edges| {
+                // NOTE: Saturate as DirectedGraph::address_trace_add() does
+                let counts = edges.iter().try_fold(0u64, |sum, v| {
+                    v.label().parse::<u64>().ok().map(|v| sum.saturating_add(v))
+                });
+                match counts {
+                    Some(sum) => format!("{}", sum),
+                    None => edges[0].label().clone(),
+                }
+            },
+        );
+
+        let mut graph = DirectedGraph::new(self.name().clone());
+        for node in quotient.nodes() {
+            let name = match symbolizer.function_of(node.index().0) {
+                Some((_, name)) => String::from(name),
+                None => format!("{}", node.index()),
+            };
+            graph.add_node(&SymbolNode::new(node.index(), &name));
+        }
+        for edge in quotient.edges() {
+            graph.add_edge(&SymbolEdge::new(
+                edge.parent(),
+                edge.child(),
+                edge.label().clone(),
+            ));
+        }
+        for edge in quotient.weak_edges() {
+            graph.add_weak_edge(&SymbolEdge::new(
+                edge.parent(),
+                edge.child(),
+                edge.label().clone(),
+            ));
+        }
+        graph
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    extern crate std;
+
+    use crate::edge::basic_edge::BasicEdge;
+    use crate::edge::directed_edge::DirectedEdge;
+    use crate::edge::Edge;
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::importer::elf_symbol::{Address, ElfSymbolizer};
+    use crate::node::basic_node::BasicNode;
+
+    use alloc::format;
+    use alloc::string::String;
+
+    #[no_mangle]
+    #[inline(never)]
+    extern "C" fn teapot_elf_symbol_test_function(x: u64) -> u64 {
+        x.wrapping_mul(31).rotate_left(7) ^ 0x5a5a
+    }
+
+    fn open_self() -> ElfSymbolizer {
+        // Keep the function alive in the test binary
+        assert_ne!(teapot_elf_symbol_test_function(1), 0);
+        ElfSymbolizer::open(std::env::current_exe().unwrap(), true).unwrap()
+    }
+
+    #[test]
+    fn test_elf_symbolizer_label_of() {
+        let symbolizer = open_self();
+        let address = symbolizer
+            .address_of("teapot_elf_symbol_test_function")
+            .unwrap();
+
+        assert_eq!(
+            symbolizer.function_of(address + 1),
+            Some((address, "teapot_elf_symbol_test_function"))
+        );
+        let label = symbolizer.label_of(address + 1);
+        assert!(label.starts_with("teapot_elf_symbol_test_function+0x1"));
+        assert!(label.contains("(elf_symbol.rs:"), "{}", label);
+        assert_eq!(symbolizer.function_of(1), None);
+    }
+
+    #[test]
+    fn test_directed_graph_coarsen_by_function() {
+        type Edge = BasicEdge<BasicNode<u64>>;
+        let symbolizer = open_self();
+        let function = symbolizer
+            .address_of("teapot_elf_symbol_test_function")
+            .unwrap();
+
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (parent, child, count) in [
+            (1, function, 2),
+            (function, function + 1, 3),
+            (function + 1, 1, 4),
+            (2, function + 1, 5),
+        ] {
+            graph.add_edge(&Edge::new(&parent, &child, format!("{}", count)));
+        }
+
+        let symbolized = graph.symbolize(&symbolizer);
+        assert_eq!(symbolized.nodes().len(), 4);
+        assert_eq!(
+            symbolized
+                .get_node(&Address(function))
+                .map(|v| format!("{}", v)),
+            Some(format!(
+                "{} ({:#x})",
+                symbolizer.label_of(function),
+                function
+            ))
+        );
+
+        let coarsened = graph.coarsen_by_function(&symbolizer);
+        assert_eq!(coarsened.nodes().len(), 3);
+        assert_eq!(
+            coarsened
+                .get_node(&Address(function))
+                .map(|v| v.metadata().clone()),
+            Some(String::from("teapot_elf_symbol_test_function"))
+        );
+        let label_of = |parent: u64, child: u64| {
+            let arrow = DirectedEdge::new(&Address(parent), &Address(child));
+            coarsened
+                .get_edge(&arrow)
+                .or_else(|| coarsened.get_weak_edge(&arrow))
+                .map(|v| v.label().clone())
+        };
+        assert_eq!(label_of(1, function), Some(String::from("2")));
+        assert_eq!(label_of(function, function), Some(String::from("3")));
+        assert_eq!(label_of(2, function), Some(String::from("5")));
+    }
+
+    #[test]
+    fn test_directed_graph_coarsen_by_function_saturates() {
+        type Edge = BasicEdge<BasicNode<u64>>;
+        let symbolizer = open_self();
+        let function = symbolizer
+            .address_of("teapot_elf_symbol_test_function")
+            .unwrap();
+
+        let mut graph = DirectedGraph::new(String::from("test"));
+        for (parent, child, count) in [
+            (1, function, u64::MAX),
+            (1, function + 1, u64::MAX),
+            (2, function, u64::MAX),
+            (2, function + 1, 1),
+        ] {
+            graph.add_edge(&Edge::new(&parent, &child, format!("{}", count)));
+        }
+
+        let coarsened = graph.coarsen_by_function(&symbolizer);
+        for parent in [1, 2] {
+            let arrow = DirectedEdge::new(&Address(parent), &Address(function));
+            assert_eq!(
+                coarsened.get_edge(&arrow).map(|v| v.label().clone()),
+                Some(format!("{}", u64::MAX))
+            );
+        }
+    }
+}
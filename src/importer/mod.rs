@@ -0,0 +1,4 @@
+pub mod address_trace;
+pub mod afl_queue;
+#[cfg(feature = "elf")]
+pub mod elf_symbol;
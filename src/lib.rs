@@ -19,6 +19,7 @@ use std::io;
 pub mod edge;
 pub mod error;
 pub mod graph;
+pub mod importer;
 pub mod node;
 pub mod result;
 
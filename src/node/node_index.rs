@@ -8,4 +8,6 @@ pub trait NodeIndex:
 }
 
 impl NodeIndex for usize {}
+impl NodeIndex for u32 {}
+impl NodeIndex for u64 {}
 impl NodeIndex for String {}
@@ -6,24 +6,32 @@ graph [
     label "node_1"
     rank 0
     is_root 1
+    subtree_size 4
+    out_degree 2
   ]
   node [
     id 1
     label "node_2"
     rank 1
     is_root 0
+    subtree_size 1
+    out_degree 0
   ]
   node [
     id 2
     label "node_3"
     rank 1
     is_root 0
+    subtree_size 2
+    out_degree 1
   ]
   node [
     id 3
     label "node_4"
     rank 2
     is_root 0
+    subtree_size 1
+    out_degree 0
   ]
   edge [
     source 0
@@ -40,4 +48,16 @@ graph [
     target 3
     label "3->4"
   ]
+  edge [
+    source 3
+    target 0
+    label "4->1"
+    weak 1
+  ]
+  edge [
+    source 3
+    target 3
+    label "4->4"
+    weak 1
+  ]
 ]
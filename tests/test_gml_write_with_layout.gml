@@ -0,0 +1,95 @@
+graph [
+  directed 1
+  name "test"
+  node [
+    id 0
+    label "1"
+    graphics [
+      x 70
+      y 32
+      w 40
+      h 24
+    ]
+  ]
+  node [
+    id 1
+    label "2"
+    graphics [
+      x 40
+      y 106
+      w 40
+      h 24
+    ]
+  ]
+  node [
+    id 2
+    label "3"
+    graphics [
+      x 100
+      y 106
+      w 40
+      h 24
+    ]
+  ]
+  node [
+    id 3
+    label "4"
+    graphics [
+      x 100
+      y 180
+      w 40
+      h 24
+    ]
+  ]
+  edge [
+    source 0
+    target 1
+    label "1->2"
+    graphics [
+      Line [
+        point [
+          x 70
+          y 44
+        ]
+        point [
+          x 40
+          y 94
+        ]
+      ]
+    ]
+  ]
+  edge [
+    source 0
+    target 2
+    label "1->3"
+    graphics [
+      Line [
+        point [
+          x 70
+          y 44
+        ]
+        point [
+          x 100
+          y 94
+        ]
+      ]
+    ]
+  ]
+  edge [
+    source 2
+    target 3
+    label "3->4"
+    graphics [
+      Line [
+        point [
+          x 100
+          y 118
+        ]
+        point [
+          x 100
+          y 168
+        ]
+      ]
+    ]
+  ]
+]